@@ -0,0 +1,154 @@
+//! Compares `ArenaList` with the boxed `LinkedList`.
+//!
+//! Runs on stable as a plain binary:
+//!
+//! ```sh
+//! cargo bench --bench lists
+//! ```
+use doubly_linked_list::{ArenaList, LinkedList};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// The operations both lists are put through, `touch` moves
+/// the element at the given position to the front.
+trait List: Default {
+    fn push_back(&mut self, n: u64);
+    fn pop_front(&mut self) -> Option<u64>;
+    fn sum(&self) -> u64;
+    fn touch(&mut self, position: usize);
+}
+
+impl List for LinkedList<u64> {
+    fn push_back(&mut self, n: u64) {
+        LinkedList::push_back(self, n);
+    }
+
+    fn pop_front(&mut self) -> Option<u64> {
+        LinkedList::pop_front(self)
+    }
+
+    fn sum(&self) -> u64 {
+        self.iter().sum()
+    }
+
+    // without handles the element has to be walked to
+    fn touch(&mut self, position: usize) {
+        let mut cursor = self.cursor_front();
+        cursor.seek_forward(position);
+        if let Some(n) = cursor.take() {
+            self.push_front(n);
+        }
+    }
+}
+
+/// `ArenaList` along with the handles of its elements, by position.
+#[derive(Default)]
+struct Arena {
+    list: ArenaList<u64>,
+    handles: Vec<doubly_linked_list::NodeHandle>,
+}
+
+impl List for Arena {
+    fn push_back(&mut self, n: u64) {
+        self.handles.push(self.list.push_back(n));
+    }
+
+    fn pop_front(&mut self) -> Option<u64> {
+        self.list.pop_front()
+    }
+
+    fn sum(&self) -> u64 {
+        self.list.iter().sum()
+    }
+
+    // the positions are those at the time of the push,
+    // close enough for the cost of a move
+    fn touch(&mut self, position: usize) {
+        self.list.move_to_front(self.handles[position]);
+    }
+}
+
+// xorshift, for positions that do not favour either list
+fn positions(len: usize, count: usize) -> Vec<usize> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % len as u64) as usize
+        })
+        .collect()
+}
+
+fn time(f: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+/// Fills a list with `len` elements, then pops them all.
+fn churn<L: List>(len: u64) -> Duration {
+    time(|| {
+        let mut list = L::default();
+        for n in 0..len {
+            list.push_back(n);
+        }
+        while let Some(n) = list.pop_front() {
+            black_box(n);
+        }
+    })
+}
+
+/// Sums up a list of `len` elements, `rounds` times.
+fn iterate<L: List>(len: u64, rounds: usize) -> Duration {
+    let mut list = L::default();
+    for n in 0..len {
+        list.push_back(n);
+    }
+    time(|| {
+        for _ in 0..rounds {
+            black_box(list.sum());
+        }
+    })
+}
+
+/// Moves `count` random elements of a list of `len` to the front,
+/// what an LRU cache does on every hit.
+fn touch<L: List>(len: usize, count: usize) -> Duration {
+    let mut list = L::default();
+    for n in 0..len as u64 {
+        list.push_back(n);
+    }
+    let positions = positions(len, count);
+    time(|| {
+        for &p in &positions {
+            list.touch(p);
+        }
+        black_box(&list);
+    })
+}
+
+fn main() {
+    println!("{:<24} {:>14} {:>14}", "case", "arena", "boxed");
+    let cases: Vec<(&str, Duration, Duration)> = vec![
+        (
+            "churn 1000000",
+            churn::<Arena>(1_000_000),
+            churn::<LinkedList<u64>>(1_000_000),
+        ),
+        (
+            "iterate 100000 x100",
+            iterate::<Arena>(100_000, 100),
+            iterate::<LinkedList<u64>>(100_000, 100),
+        ),
+        (
+            "touch 10000 x1000",
+            touch::<Arena>(10_000, 1_000),
+            touch::<LinkedList<u64>>(10_000, 1_000),
+        ),
+    ];
+    for (name, arena, boxed) in cases {
+        println!("{:<24} {:>14?} {:>14?}", name, arena, boxed);
+    }
+}
@@ -0,0 +1,10 @@
+#!/bin/sh
+# Runs the stress tests under Miri, which catches any undefined behaviour
+# or leak in the unsafe code of the list. Miri comes with nightly:
+#
+#     rustup +nightly component add miri
+#
+# Extra arguments go to `cargo miri test`, e.g. `./miri.sh --features advanced`.
+set -e
+cd "$(dirname "$0")"
+cargo +nightly miri test --test stress "$@"
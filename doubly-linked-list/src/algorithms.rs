@@ -0,0 +1,155 @@
+//! Bulk operations on the whole list, all done by relinking the nodes
+//! with the cursor, so that no value is moved and the references taken
+//! to them before point to the same values after.
+
+use crate::{Cursor, LinkedList};
+use std::cmp::Ordering;
+
+pub struct DrainFilter<'a, T, F> {
+    cursor: Cursor<'a, T>,
+    filter: F,
+    // the back element was taken, the cursor stepped back to visited ones
+    done: bool,
+}
+
+impl<T> LinkedList<T> {
+    // Stable merge sort.
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        self.merge_sort(&mut compare);
+    }
+
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut f: F) {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+
+    fn merge_sort<F: FnMut(&T, &T) -> Ordering>(&mut self, compare: &mut F) {
+        if self.len < 2 {
+            return;
+        }
+        let half = self.len / 2;
+        let mut cursor = self.cursor_front();
+        cursor.seek_forward(half - 1);
+        let mut back = cursor.split_after();
+        self.merge_sort(compare);
+        back.merge_sort(compare);
+        self.merge_by(back, compare);
+    }
+
+    // Merges another sorted list into this sorted one, the elements
+    // of this one go first among the equal ones.
+    pub fn merge(&mut self, other: LinkedList<T>)
+    where
+        T: Ord,
+    {
+        self.merge_by(other, T::cmp);
+    }
+
+    pub fn merge_by<F: FnMut(&T, &T) -> Ordering>(
+        &mut self,
+        mut other: LinkedList<T>,
+        mut compare: F,
+    ) {
+        let mut cursor = self.cursor_front();
+        while let Some(first) = other.front() {
+            match cursor.peek_mut() {
+                Some(current) if compare(first, current) == Ordering::Less => {
+                    let rest = other.cursor_front().split_after();
+                    cursor.splice_before(std::mem::replace(&mut other, rest));
+                }
+                Some(_) => {
+                    cursor.next();
+                }
+                // past the back, the rest goes there
+                None => {
+                    cursor.splice_before(other);
+                    return;
+                }
+            }
+        }
+    }
+
+    // Keeps only the elements `f` returns true for, in order.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.retain_mut(|v| f(v));
+    }
+
+    pub fn retain_mut<F: FnMut(&mut T) -> bool>(&mut self, mut f: F) {
+        self.drain_filter(|v| !f(v)).for_each(drop);
+    }
+
+    // Removes the consecutive elements `same_bucket` returns true for,
+    // called with each element and the last one kept before it.
+    pub fn dedup_by<F: FnMut(&mut T, &mut T) -> bool>(&mut self, mut same_bucket: F) {
+        let mut cursor = self.cursor_front();
+        while let Some(kept) = cursor.current {
+            cursor.next();
+            while let Some(current) = cursor.current {
+                // two different nodes of the list, which is borrowed mutably
+                let same = unsafe {
+                    same_bucket(&mut (*current.as_ptr()).value, &mut (*kept.as_ptr()).value)
+                };
+                if !same {
+                    break;
+                }
+                let last = cursor.at_back();
+                cursor.take();
+                if last {
+                    return;
+                }
+            }
+        }
+    }
+
+    pub fn dedup_by_key<K: PartialEq, F: FnMut(&mut T) -> K>(&mut self, mut key: F) {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    /// Return an iterator removing the elements `filter` returns true for,
+    /// from front to back, as it goes: those not reached yet stay in the list
+    /// if it is dropped early
+    pub fn drain_filter<F: FnMut(&mut T) -> bool>(&mut self, filter: F) -> DrainFilter<'_, T, F> {
+        DrainFilter {
+            cursor: self.cursor_front(),
+            filter,
+            done: false,
+        }
+    }
+}
+
+impl<T> Cursor<'_, T> {
+    fn at_back(&self) -> bool {
+        // a node of the list
+        self.current
+            .is_some_and(|current| unsafe { (*current.as_ptr()).next.is_none() })
+    }
+}
+
+impl<T, F: FnMut(&mut T) -> bool> Iterator for DrainFilter<'_, T, F> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while !self.done {
+            let current = self.cursor.peek_mut()?;
+            if (self.filter)(current) {
+                self.done = self.cursor.at_back();
+                return self.cursor.take();
+            }
+            self.cursor.next();
+        }
+        None
+    }
+}
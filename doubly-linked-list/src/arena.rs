@@ -0,0 +1,495 @@
+//! The same list with all of its nodes in a single `Vec`,
+//! linked by their indexes instead of pointers.
+//!
+//! A removed node leaves its slot to the next inserted one, a handle
+//! to it carries the generation of the slot it was given for,
+//! so that it does not resolve to whatever lives there next.
+
+/// A stable reference to an element of an `ArenaList`,
+/// valid until the element is removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeHandle {
+    index: usize,
+    generation: usize,
+}
+
+struct Node<T> {
+    value: T,
+    next: Option<usize>,
+    prev: Option<usize>,
+}
+
+struct Slot<T> {
+    generation: usize,
+    node: Option<Node<T>>,
+}
+
+pub struct ArenaList<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+    head: Option<usize>,
+    back: Option<usize>,
+    len: usize,
+}
+
+/// Same as `Cursor`, for an `ArenaList`.
+pub struct ArenaCursor<'a, T> {
+    list: &'a mut ArenaList<T>,
+    current: Option<usize>,
+}
+
+pub struct ArenaIter<'a, T> {
+    list: &'a ArenaList<T>,
+    front: Option<usize>,
+    back: Option<usize>,
+    len: usize,
+}
+
+impl<T> ArenaList<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    // Room for `capacity` elements before the arena has to grow.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            head: None,
+            back: None,
+            len: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.head.map(|i| &self.node(i).value)
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.head.map(move |i| &mut self.node_mut(i).value)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.back.map(|i| &self.node(i).value)
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.back.map(move |i| &mut self.node_mut(i).value)
+    }
+
+    pub fn push_front(&mut self, element: T) -> NodeHandle {
+        let index = self.alloc(element);
+        self.link(None, self.head, index);
+        self.handle(index)
+    }
+
+    pub fn push_back(&mut self, element: T) -> NodeHandle {
+        let index = self.alloc(element);
+        self.link(self.back, None, index);
+        self.handle(index)
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|i| self.take(i))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.back.map(|i| self.take(i))
+    }
+
+    pub fn front_handle(&self) -> Option<NodeHandle> {
+        self.head.map(|i| self.handle(i))
+    }
+
+    pub fn back_handle(&self) -> Option<NodeHandle> {
+        self.back.map(|i| self.handle(i))
+    }
+
+    pub fn get(&self, handle: NodeHandle) -> Option<&T> {
+        self.resolve(handle).map(|i| &self.node(i).value)
+    }
+
+    pub fn get_mut(&mut self, handle: NodeHandle) -> Option<&mut T> {
+        let index = self.resolve(handle)?;
+        Some(&mut self.node_mut(index).value)
+    }
+
+    // Removes the element wherever it is in the list.
+    //
+    // Returns None if it is no longer there.
+    pub fn remove(&mut self, handle: NodeHandle) -> Option<T> {
+        let index = self.resolve(handle)?;
+        Some(self.take(index))
+    }
+
+    // Moves the element to the front, its handle stays valid.
+    //
+    // Returns false if it is no longer in the list.
+    pub fn move_to_front(&mut self, handle: NodeHandle) -> bool {
+        match self.resolve(handle) {
+            Some(index) => {
+                if self.head != Some(index) {
+                    self.unlink(index);
+                    self.link(None, self.head, index);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Same as `move_to_front`, to the back.
+    pub fn move_to_back(&mut self, handle: NodeHandle) -> bool {
+        match self.resolve(handle) {
+            Some(index) => {
+                if self.back != Some(index) {
+                    self.unlink(index);
+                    self.link(self.back, None, index);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Return a cursor positioned on the front element
+    pub fn cursor_front(&mut self) -> ArenaCursor<'_, T> {
+        ArenaCursor {
+            current: self.head,
+            list: self,
+        }
+    }
+
+    /// Return a cursor positioned on the back element
+    pub fn cursor_back(&mut self) -> ArenaCursor<'_, T> {
+        ArenaCursor {
+            current: self.back,
+            list: self,
+        }
+    }
+
+    /// Return a cursor positioned on the given element,
+    /// None if it is no longer in the list
+    pub fn cursor_at(&mut self, handle: NodeHandle) -> Option<ArenaCursor<'_, T>> {
+        let current = Some(self.resolve(handle)?);
+        Some(ArenaCursor {
+            current,
+            list: self,
+        })
+    }
+
+    /// Return an iterator that moves from front to back
+    pub fn iter(&self) -> ArenaIter<'_, T> {
+        ArenaIter {
+            list: self,
+            front: self.head,
+            back: self.back,
+            len: self.len,
+        }
+    }
+
+    fn node(&self, index: usize) -> &Node<T> {
+        self.slots[index]
+            .node
+            .as_ref()
+            .expect("a free slot in the list")
+    }
+
+    fn node_mut(&mut self, index: usize) -> &mut Node<T> {
+        self.slots[index]
+            .node
+            .as_mut()
+            .expect("a free slot in the list")
+    }
+
+    fn handle(&self, index: usize) -> NodeHandle {
+        NodeHandle {
+            index,
+            generation: self.slots[index].generation,
+        }
+    }
+
+    fn resolve(&self, handle: NodeHandle) -> Option<usize> {
+        self.slots
+            .get(handle.index)
+            .filter(|slot| slot.generation == handle.generation && slot.node.is_some())
+            .map(|_| handle.index)
+    }
+
+    // A node in a free slot, not linked to anything yet.
+    fn alloc(&mut self, value: T) -> usize {
+        let node = Some(Node {
+            value,
+            next: None,
+            prev: None,
+        });
+        match self.free.pop() {
+            Some(index) => {
+                self.slots[index].node = node;
+                index
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    node,
+                });
+                self.slots.len() - 1
+            }
+        }
+    }
+
+    // Unlinks the node and frees its slot.
+    fn take(&mut self, index: usize) -> T {
+        self.unlink(index);
+        let slot = &mut self.slots[index];
+        slot.generation += 1;
+        self.free.push(index);
+        slot.node.take().unwrap().value
+    }
+
+    // Links an unlinked node between `prev` and `next`, which have to be
+    // next to each other, None standing for the ends of the list.
+    fn link(&mut self, prev: Option<usize>, next: Option<usize>, index: usize) {
+        let node = self.node_mut(index);
+        node.prev = prev;
+        node.next = next;
+        match prev {
+            Some(prev) => self.node_mut(prev).next = Some(index),
+            None => self.head = Some(index),
+        }
+        match next {
+            Some(next) => self.node_mut(next).prev = Some(index),
+            None => self.back = Some(index),
+        }
+        self.len += 1;
+    }
+
+    fn unlink(&mut self, index: usize) {
+        let node = self.node_mut(index);
+        let (prev, next) = (node.prev.take(), node.next.take());
+        match prev {
+            Some(prev) => self.node_mut(prev).next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => self.node_mut(next).prev = prev,
+            None => self.back = prev,
+        }
+        self.len -= 1;
+    }
+}
+
+impl<T> Default for ArenaList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Past either end the cursor is at no element, just like `Cursor`.
+impl<T> ArenaCursor<'_, T> {
+    /// Take a mutable reference to the current element
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        let list = &mut *self.list;
+        self.current.map(move |i| &mut list.node_mut(i).value)
+    }
+
+    /// The handle of the current element
+    pub fn handle(&self) -> Option<NodeHandle> {
+        self.current.map(|i| self.list.handle(i))
+    }
+
+    /// Move one position forward (towards the back) and
+    /// return a reference to the new position
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&mut T> {
+        if let Some(current) = self.current {
+            self.current = self.list.node(current).next;
+        }
+        self.peek_mut()
+    }
+
+    /// Move one position backward (towards the front) and
+    /// return a reference to the new position
+    pub fn prev(&mut self) -> Option<&mut T> {
+        let current = self.current?;
+        self.current = self.list.node(current).prev;
+        self.peek_mut()
+    }
+
+    /// Remove and return the element at the current position and move the cursor
+    /// to the neighboring element that's closest to the back. This can be
+    /// either the next or previous position.
+    pub fn take(&mut self) -> Option<T> {
+        let current = self.current?;
+        let node = self.list.node(current);
+        self.current = node.next.or(node.prev);
+        Some(self.list.take(current))
+    }
+
+    pub fn insert_after(&mut self, element: T) {
+        let (prev, next) = match self.current {
+            Some(current) => (Some(current), self.list.node(current).next),
+            None => (None, self.list.head),
+        };
+        self.insert(prev, next, element);
+    }
+
+    pub fn insert_before(&mut self, element: T) {
+        let (prev, next) = match self.current {
+            Some(current) => (self.list.node(current).prev, Some(current)),
+            None => (self.list.back, None),
+        };
+        self.insert(prev, next, element);
+    }
+
+    fn insert(&mut self, prev: Option<usize>, next: Option<usize>, element: T) {
+        // nothing to be next to in an empty list, the new element
+        // becomes the current one
+        let was_empty = self.list.is_empty();
+        let index = self.list.alloc(element);
+        self.list.link(prev, next, index);
+        if was_empty {
+            self.current = Some(index);
+        }
+    }
+
+    /// Move everything after the current element into a new list.
+    /// Past either end, the whole list is moved.
+    ///
+    /// Unlike with `Cursor`, the elements are moved one by one into the arena
+    /// of the other list, so that their handles do not resolve in this one anymore.
+    pub fn split_after(&mut self) -> ArenaList<T> {
+        let current = match self.current {
+            Some(current) => current,
+            None => return std::mem::take(self.list),
+        };
+        let mut other = ArenaList::new();
+        while let Some(next) = self.list.node(current).next {
+            other.push_back(self.list.take(next));
+        }
+        other
+    }
+
+    /// Move everything before the current element into a new list.
+    /// Past either end, the whole list is moved.
+    pub fn split_before(&mut self) -> ArenaList<T> {
+        let current = match self.current {
+            Some(current) => current,
+            None => return std::mem::take(self.list),
+        };
+        let mut other = ArenaList::new();
+        while let Some(prev) = self.list.node(current).prev {
+            other.push_front(self.list.take(prev));
+        }
+        other
+    }
+
+    /// Insert the elements of `other` after the current element,
+    /// or at the front of the list when past either end.
+    pub fn splice_after(&mut self, other: ArenaList<T>) {
+        let (prev, next) = match self.current {
+            Some(current) => (Some(current), self.list.node(current).next),
+            None => (None, self.list.head),
+        };
+        self.splice(prev, next, other);
+    }
+
+    /// Insert the elements of `other` before the current element,
+    /// or at the back of the list when past either end.
+    pub fn splice_before(&mut self, other: ArenaList<T>) {
+        let (prev, next) = match self.current {
+            Some(current) => (self.list.node(current).prev, Some(current)),
+            None => (self.list.back, None),
+        };
+        self.splice(prev, next, other);
+    }
+
+    // The elements go between `prev` and `next` in order, the cursor
+    // stays where it is, past the ends even if the list was empty.
+    fn splice(&mut self, mut prev: Option<usize>, next: Option<usize>, mut other: ArenaList<T>) {
+        while let Some(element) = other.pop_front() {
+            let index = self.list.alloc(element);
+            self.list.link(prev, next, index);
+            prev = Some(index);
+        }
+    }
+
+    pub fn seek_forward(&mut self, n: usize) -> bool {
+        (0..n).all(|_| self.next().is_some())
+    }
+
+    pub fn seek_backward(&mut self, n: usize) -> bool {
+        (0..n).all(|_| self.prev().is_some())
+    }
+}
+
+impl<'a, T> Iterator for ArenaIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.list.node(self.front?);
+        self.len -= 1;
+        self.front = node.next;
+        Some(&node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for ArenaIter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.list.node(self.back?);
+        self.len -= 1;
+        self.back = node.prev;
+        Some(&node.value)
+    }
+}
+
+impl<T> ExactSizeIterator for ArenaIter<'_, T> {}
+
+impl<'a, T> IntoIterator for &'a ArenaList<T> {
+    type Item = &'a T;
+    type IntoIter = ArenaIter<'a, T>;
+
+    fn into_iter(self) -> ArenaIter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T> std::iter::FromIterator<T> for ArenaList<T> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for ArenaList<T> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for elem in iter {
+            self.push_back(elem);
+        }
+    }
+}
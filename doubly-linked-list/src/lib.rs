@@ -1,41 +1,98 @@
-//! I tried to still have some ownership in the `node.next`
-//! chain, but as you can see this ended up being a mess,
-//! I'd better make all of it on pointers and know no troubles.
+//! All the links between the nodes are raw pointers, the list owns
+//! every node as if it held a `Box` of each one.
+//!
+//! Invariants the unsafe code relies on:
+//! - every node is allocated by `Node::alloc` and freed once, when it
+//!   is taken out of the list or the list is dropped;
+//! - `head` and `back` are both None or point to the ends of the chain,
+//!   the `next` and `prev` links of each node mirror each other;
+//! - `len` is the number of nodes in the chain;
+//! - no reference to a node outlives the borrow of the list it came from,
+//!   and the only references to whole nodes are shared ones, the values
+//!   are borrowed mutably field by field.
 
 // this module adds some functionality based on the required implementations
 // here like: `LinkedList::pop_back` or `Clone for LinkedList<T>`
 // You are free to use anything in it, but it's mainly for the test framework.
 mod pre_implemented;
 
-use std::ptr::{self, NonNull};
+mod algorithms;
+mod arena;
+mod lru;
+#[cfg(feature = "serde")]
+mod serialize;
+
+pub use algorithms::DrainFilter;
+pub use arena::{ArenaCursor, ArenaIter, ArenaList, NodeHandle};
+pub use lru::{LruCache, LruIter};
+
+use std::marker::PhantomData;
+use std::ptr::NonNull;
 
 struct Node<T> {
     value: T,
     next: Link<T>,
-    prev: Option<NonNull<Node<T>>>,
+    prev: Link<T>,
 }
 
-type Link<T> = Option<Box<Node<T>>>;
+type Ptr<T> = NonNull<Node<T>>;
+type Link<T> = Option<Ptr<T>>;
 
 pub struct LinkedList<T> {
     head: Link<T>,
-    back: Option<NonNull<Node<T>>>,
+    back: Link<T>,
+    // kept up to date by the cursor, the only one changing the list
+    len: usize,
+    // owns the nodes, for the drop check and covariance over T
+    _nodes: PhantomData<Box<Node<T>>>,
 }
 
 pub struct Cursor<'a, T> {
     list: &'a mut LinkedList<T>,
-    current: Option<NonNull<Node<T>>>,
+    current: Link<T>,
+    // position of `current`, or the length of the list when it is None,
+    // not known yet for a cursor from `cursor_at`, see `Cursor::index`
+    index: Option<usize>,
+}
+
+// A reference to an element of a `LinkedList`, to get back to it
+// with `LinkedList::cursor_at` without walking the list, for `LruCache`.
+//
+// It follows the element wherever it goes, even to another list
+// with `split_*` and `splice_*`, up until the element is taken out.
+pub(crate) struct Handle<T>(Ptr<T>);
+
+// Both ends move towards each other, `len` tells when they have met.
+pub struct Iter<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    _list: PhantomData<&'a Node<T>>,
 }
 
-pub struct Iter<'a, T>(&'a Link<T>);
+pub struct IterMut<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    _list: PhantomData<&'a mut Node<T>>,
+}
+
+pub struct IntoIter<T>(LinkedList<T>);
 
 impl<T> Node<T> {
-    pub fn boxed(value: T) -> Box<Node<T>> {
-        Box::new(Self {
+    // A node not linked to anything yet.
+    fn alloc(value: T) -> Ptr<T> {
+        NonNull::from(Box::leak(Box::new(Self {
             value,
             next: None,
             prev: None,
-        })
+        })))
+    }
+
+    // Safety: `node` comes from `alloc`, is no longer linked
+    // and is not used again.
+    unsafe fn free(node: Ptr<T>) -> T {
+        Box::from_raw(node.as_ptr()).value
     }
 }
 
@@ -44,6 +101,8 @@ impl<'a, T> LinkedList<T> {
         Self {
             head: None,
             back: None,
+            len: 0,
+            _nodes: PhantomData,
         }
     }
 
@@ -57,211 +116,498 @@ impl<'a, T> LinkedList<T> {
     }
 
     pub fn len(&self) -> usize {
-        self.iter().count()
+        self.len
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        // the node lives as long as the list is borrowed
+        self.head.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        // same as `front`, and the list is borrowed mutably
+        self.head.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        // same as `front`
+        self.back.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        // same as `front_mut`
+        self.back.map(|node| unsafe { &mut (*node.as_ptr()).value })
     }
 
     /// Return a cursor positioned on the front element
     pub fn cursor_front(&'a mut self) -> Cursor<'a, T> {
-        let current = if let Some(head) = &mut self.head {
-            unsafe { Some(NonNull::new_unchecked(head.as_mut())) }
-        } else {
-            None
-        };
-
         Cursor {
+            current: self.head,
+            index: Some(0),
             list: self,
-            current,
         }
     }
+
     /// Return a cursor positioned on the back element
     pub fn cursor_back(&'a mut self) -> Cursor<'a, T> {
-        let current = self.back;
+        Cursor {
+            current: self.back,
+            index: Some(self.len.saturating_sub(1)),
+            list: self,
+        }
+    }
 
+    // A cursor positioned on the element of the handle.
+    //
+    // Safety: the element is in this very list, not taken out of it
+    // nor moved to another one since the handle was made.
+    pub(crate) unsafe fn cursor_at(&'a mut self, handle: Handle<T>) -> Cursor<'a, T> {
         Cursor {
+            current: Some(handle.0),
+            index: None,
             list: self,
-            current,
         }
     }
 
+    // Safety: same as `cursor_at`.
+    pub(crate) unsafe fn get(&self, handle: Handle<T>) -> &T {
+        &(*handle.0.as_ptr()).value
+    }
+
+    pub(crate) fn front_handle(&self) -> Option<Handle<T>> {
+        self.head.map(Handle)
+    }
+
     /// Return an iterator that moves from front to back
     pub fn iter(&'a self) -> Iter<'a, T> {
-        Iter(&self.head)
+        Iter {
+            front: self.head,
+            back: self.back,
+            len: self.len,
+            _list: PhantomData,
+        }
+    }
+
+    /// Return an iterator that moves from front to back
+    /// and allows modifying each value
+    pub fn iter_mut(&'a mut self) -> IterMut<'a, T> {
+        IterMut {
+            front: self.head,
+            back: self.back,
+            len: self.len,
+            _list: PhantomData,
+        }
+    }
+
+    // A list made of the `len` nodes from `first` to `last`.
+    //
+    // Safety: the nodes are linked to each other only
+    // and owned by nothing else.
+    unsafe fn from_chain(first: Ptr<T>, last: Ptr<T>, len: usize) -> Self {
+        Self {
+            head: Some(first),
+            back: Some(last),
+            len,
+            _nodes: PhantomData,
+        }
+    }
+
+    // Leaves the list empty, handing out its nodes from the first to the last
+    // and their count.
+    fn detach(&mut self) -> Option<(Ptr<T>, Ptr<T>, usize)> {
+        let first = self.head.take()?;
+        let last = self.back.take()?;
+        Some((first, last, std::mem::take(&mut self.len)))
+    }
+
+    // Links the `len` nodes from `first` to `last` between `prev` and `next`,
+    // None standing for the ends of the list.
+    //
+    // Safety: `prev` and `next` are next to each other in this list,
+    // the nodes are linked to each other only and owned by nothing else.
+    unsafe fn link(
+        &mut self,
+        prev: Link<T>,
+        next: Link<T>,
+        first: Ptr<T>,
+        last: Ptr<T>,
+        len: usize,
+    ) {
+        (*first.as_ptr()).prev = prev;
+        (*last.as_ptr()).next = next;
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = Some(first),
+            None => self.head = Some(first),
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).prev = Some(last),
+            None => self.back = Some(last),
+        }
+        self.len += len;
+    }
+
+    // The opposite of `link`, the nodes are left linked to each other only.
+    //
+    // Safety: the `len` nodes from `first` to `last` are in this list.
+    unsafe fn unlink(&mut self, first: Ptr<T>, last: Ptr<T>, len: usize) {
+        let prev = (*first.as_ptr()).prev.take();
+        let next = (*last.as_ptr()).next.take();
+        match prev {
+            Some(prev) => (*prev.as_ptr()).next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => (*next.as_ptr()).prev = prev,
+            None => self.back = prev,
+        }
+        self.len -= len;
+    }
+}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl<T> Drop for LinkedList<T> {
     fn drop(&mut self) {
         let mut cursor = self.cursor_front();
-        while let Some(_) = cursor.take() {}
+        while cursor.take().is_some() {}
     }
 }
 
 // the cursor is expected to act as if it is at the position of an element
 // and it also has to work with and be able to insert into an empty list.
+//
+// Past either end the cursor is at no element, `next` and `prev` do not
+// move it from there and inserting puts the new elements at the ends:
+// after it is the front of the list and before it is the back.
 impl<T> Cursor<'_, T> {
     /// Take a mutable reference to the current element
     pub fn peek_mut(&mut self) -> Option<&mut T> {
-        unsafe {
-            self.current
-                .as_mut()
-                .map(|current| &mut current.as_mut().value)
-        }
+        // the list is borrowed mutably through the cursor
+        self.current
+            .map(|current| unsafe { &mut (*current.as_ptr()).value })
     }
 
     /// Move one position forward (towards the back) and
     /// return a reference to the new position
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Option<&mut T> {
-        unsafe {
-            if let Some(current) = self.current.as_mut() {
-                self.current = if let Some(node) = &mut current.as_mut().next {
-                    Some(NonNull::new_unchecked(node.as_mut()))
-                } else {
-                    None
-                };
+        if let Some(current) = self.current {
+            // a node of the list, see `peek_mut` for the result
+            self.current = unsafe { (*current.as_ptr()).next };
+            self.index = match self.current {
+                Some(_) => self.index.map(|i| i + 1),
+                // walked off the back
+                None => Some(self.list.len),
             };
-            self.current.as_mut().map(|node| &mut node.as_mut().value)
         }
+        self.peek_mut()
     }
 
     /// Move one position backward (towards the front) and
     /// return a reference to the new position
     pub fn prev(&mut self) -> Option<&mut T> {
-        unsafe {
-            if let Some(current) = self.current.as_mut() {
-                self.current = current.as_mut().prev;
-                self.current.as_mut().map(|node| &mut node.as_mut().value)
-            } else {
-                None
-            }
-        }
+        let current = self.current?;
+        // a node of the list
+        self.current = unsafe { (*current.as_ptr()).prev };
+        self.index = match self.current {
+            Some(_) => self.index.map(|i| i - 1),
+            // walked off the front
+            None => Some(self.list.len),
+        };
+        self.peek_mut()
     }
 
     /// Remove and return the element at the current position and move the cursor
     /// to the neighboring element that's closest to the back. This can be
     /// either the next or previous position.
     pub fn take(&mut self) -> Option<T> {
-        let mut new_current = None;
-        let res = unsafe {
-            match self.current.as_mut() {
-                Some(current) => {
-                    let current_mut = current.as_mut();
-                    match current_mut.prev.as_mut() {
-                        Some(prev) => match current_mut.next.take() {
-                            Some(mut next) => {
-                                new_current = Some(NonNull::new_unchecked(next.as_mut()));
-                                next.prev = Some(*prev);
-                                // need to get current from the owner
-                                let current = prev.as_mut().next.replace(next);
-                                current.map(|node| node.value)
-                            }
-                            None => {
-                                new_current = Some(*prev);
-                                self.list.back = Some(*prev);
-                                // need to get current from the owner
-                                let current = prev.as_mut().next.take();
-                                current.map(|node| node.value)
-                            }
-                        },
-                        None => match current.as_mut().next.take() {
-                            Some(mut next) => {
-                                new_current = Some(NonNull::new_unchecked(next.as_mut()));
-                                next.prev = None;
-                                // need to get current from the owner
-                                let current = self.list.head.replace(next);
-                                current.map(|node| node.value)
-                            }
-                            None => {
-                                new_current = None;
-                                self.list.back = None;
-                                // need to get current from the owner
-                                let current = self.list.head.take();
-                                current.map(|node| node.value)
-                            }
-                        },
-                    }
+        let current = self.current?;
+        // a node of the list, freed once out of it
+        unsafe {
+            let node = &*current.as_ptr();
+            self.current = match (node.next, node.prev) {
+                (Some(next), _) => Some(next),
+                (None, prev) => {
+                    self.index = self.index.map(|i| i.saturating_sub(1));
+                    prev
                 }
-                None => None,
-            }
-        };
+            };
+            self.list.unlink(current, current, 1);
+            Some(Node::free(current))
+        }
+    }
 
-        self.current = new_current;
+    pub fn insert_after(&mut self, element: T) {
+        self.insert(element, true);
+    }
 
-        res
+    pub fn insert_before(&mut self, element: T) {
+        self.insert(element, false);
     }
 
-    pub fn insert_after(&mut self, element: T) {
-        let mut new_node = Node::boxed(element);
-        unsafe {
-            match self.current.as_mut() {
-                Some(current) => {
-                    new_node.prev = Some(*current);
-                    if let Some(next) = &mut current.as_mut().next {
-                        next.prev = Some(NonNull::new_unchecked(new_node.as_mut()));
-                    } else {
-                        // end of list
-                        self.list.back = Some(NonNull::new_unchecked(new_node.as_mut()));
-                    }
-                    // new_node now owns the node.next
-                    new_node.next = current.as_mut().next.take();
-                    current.as_mut().next = Some(new_node);
-                }
-                None => {
-                    // empty list case
-                    self.current = Some(NonNull::new_unchecked(new_node.as_mut()));
-                    self.list.back = Some(NonNull::new_unchecked(new_node.as_mut()));
-                    self.list.head = Some(new_node);
-                }
-            };
+    fn insert(&mut self, element: T, after: bool) {
+        // nothing to be next to in an empty list, the new element
+        // becomes the current one
+        let was_empty = self.list.is_empty();
+        let node = Node::alloc(element);
+        // a brand new node
+        unsafe { self.splice(node, node, 1, after) };
+        if was_empty {
+            self.current = Some(node);
+            self.index = Some(0);
+        }
+    }
+
+    /// Move everything after the current element into a new list.
+    /// Past either end, the whole list is moved.
+    pub fn split_after(&mut self) -> LinkedList<T> {
+        let current = match self.current {
+            Some(current) => current,
+            None => return self.split_all(),
         };
+        // a node of the list
+        let first = unsafe { (*current.as_ptr()).next };
+        let len = self.list.len - self.index() - 1;
+        self.split(first, self.list.back, len)
     }
 
-    pub fn insert_before(&mut self, element: T) {
-        let mut new_node = Node::boxed(element);
-        unsafe {
-            match self.current.as_mut() {
-                Some(current) => {
-                    if let Some(prev) = current.as_mut().prev.as_mut() {
-                        new_node.prev = Some(*prev);
-                        // need to get current from the owner
-                        let current = prev.as_mut().next.take();
-                        new_node.next = current;
-                        let new_prev = NonNull::new_unchecked(new_node.as_mut());
-                        prev.as_mut().next = Some(new_node);
-                        *prev = new_prev;
-                    } else {
-                        // end of list
-                        current.as_mut().prev = Some(NonNull::new_unchecked(new_node.as_mut()));
-                        new_node.prev = None;
-                        // need to get current from the owner
-                        let current = self.list.head.take();
-                        new_node.next = current;
-                        self.list.head = Some(new_node);
-                    }
-                }
-                None => {
-                    // empty list case
-                    self.current = Some(NonNull::new_unchecked(new_node.as_mut()));
-                    self.list.back = Some(NonNull::new_unchecked(new_node.as_mut()));
-                    self.list.head = Some(new_node);
-                }
-            };
+    /// Move everything before the current element into a new list.
+    /// Past either end, the whole list is moved.
+    pub fn split_before(&mut self) -> LinkedList<T> {
+        let current = match self.current {
+            Some(current) => current,
+            None => return self.split_all(),
         };
+        // a node of the list
+        let last = unsafe { (*current.as_ptr()).prev };
+        let len = self.index();
+        self.index = Some(0);
+        self.split(self.list.head, last, len)
+    }
+
+    fn split(&mut self, first: Link<T>, last: Link<T>, len: usize) -> LinkedList<T> {
+        match (first, last) {
+            // the `len` nodes in between are in the list
+            // and will be owned by the new one only
+            (Some(first), Some(last)) => unsafe {
+                self.list.unlink(first, last, len);
+                LinkedList::from_chain(first, last, len)
+            },
+            _ => LinkedList::new(),
+        }
+    }
+
+    fn split_all(&mut self) -> LinkedList<T> {
+        self.index = Some(0);
+        std::mem::take(self.list)
+    }
+
+    /// Insert the elements of `other` after the current element,
+    /// or at the front of the list when past either end.
+    pub fn splice_after(&mut self, mut other: LinkedList<T>) {
+        if let Some((first, last, len)) = other.detach() {
+            // taken over from `other`
+            unsafe { self.splice(first, last, len, true) };
+        }
+    }
+
+    /// Insert the elements of `other` before the current element,
+    /// or at the back of the list when past either end.
+    pub fn splice_before(&mut self, mut other: LinkedList<T>) {
+        if let Some((first, last, len)) = other.detach() {
+            // taken over from `other`
+            unsafe { self.splice(first, last, len, false) };
+        }
+    }
+
+    // Safety: same as `LinkedList::link`.
+    unsafe fn splice(&mut self, first: Ptr<T>, last: Ptr<T>, len: usize, after: bool) {
+        let (prev, next) = match (self.current, after) {
+            (Some(current), true) => (Some(current), (*current.as_ptr()).next),
+            (Some(current), false) => ((*current.as_ptr()).prev, Some(current)),
+            (None, true) => (None, self.list.head),
+            (None, false) => (self.list.back, None),
+        };
+        self.list.link(prev, next, first, last, len);
+        match self.current {
+            Some(_) if !after => self.index = self.index.map(|i| i + len),
+            Some(_) => {}
+            None => self.index = Some(self.list.len),
+        }
+    }
+
+    // The position of the current element, counted from the front
+    // the first time it's needed by a cursor from `cursor_at`.
+    fn index(&mut self) -> usize {
+        if let Some(index) = self.index {
+            return index;
+        }
+        let mut index = 0;
+        let mut node = self.current;
+        // nodes of the list
+        while let Some(prev) = node.and_then(|n| unsafe { (*n.as_ptr()).prev }) {
+            index += 1;
+            node = Some(prev);
+        }
+        self.index = Some(index);
+        index
     }
 }
 
+// The nodes are looked at only while there are elements left, those
+// already handed out from the other end are never touched again.
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<&'a T> {
-        if let Some(node) = self.0 {
-            self.0 = &node.next;
-            Some(&node.value)
-        } else {
-            None
+        if self.len == 0 {
+            return None;
         }
+        let node = self.front?;
+        self.len -= 1;
+        // the nodes live as long as the list is borrowed
+        unsafe {
+            self.front = (*node.as_ptr()).next;
+            Some(&(*node.as_ptr()).value)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.back?;
+        self.len -= 1;
+        // same as in `next`
+        unsafe {
+            self.back = (*node.as_ptr()).prev;
+            Some(&(*node.as_ptr()).value)
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.front?;
+        self.len -= 1;
+        // the list is borrowed mutably and each value is handed out once,
+        // the links are read without borrowing the values
+        unsafe {
+            self.front = (*node.as_ptr()).next;
+            Some(&mut (*node.as_ptr()).value)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            return None;
+        }
+        let node = self.back?;
+        self.len -= 1;
+        // same as in `next`
+        unsafe {
+            self.back = (*node.as_ptr()).prev;
+            Some(&mut (*node.as_ptr()).value)
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len, Some(self.0.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter(self)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+// The raw pointers stand for owned nodes and references to them,
+// so the list and its iterators are as thread safe as a `Box<T>`,
+// a `&T` or a `&mut T` would be.
+unsafe impl<T: Send> Send for LinkedList<T> {}
+unsafe impl<T: Sync> Sync for LinkedList<T> {}
+
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+unsafe impl<T: Send> Send for Cursor<'_, T> {}
+unsafe impl<T: Sync> Sync for Cursor<'_, T> {}
+
+// A handle gives access to nothing without the list it belongs to.
+unsafe impl<T> Send for Handle<T> {}
+unsafe impl<T> Sync for Handle<T> {}
+
+// a derive would require T: Clone, the handle does not need it
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
     }
 }
 
-unsafe impl<T> Send for LinkedList<T> {}
-unsafe impl<T> Sync for LinkedList<T> {}
+impl<T> Copy for Handle<T> {}
@@ -0,0 +1,184 @@
+//! A least recently used cache, the entries kept in a `LinkedList`
+//! from the most recently used to the least, and found by their key
+//! through the handles of their nodes.
+
+use crate::{Handle, Iter, LinkedList};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Send, so that the cache is Send whenever the entries are
+type OnEvict<K, V> = Box<dyn FnMut(K, V) + Send>;
+
+// Every handle in `handles` is the one of the entry of its key in `entries`,
+// which is what makes the calls to `cursor_at` and `get` sound.
+pub struct LruCache<K, V> {
+    entries: LinkedList<(K, V)>,
+    handles: HashMap<K, Handle<(K, V)>>,
+    capacity: usize,
+    on_evict: Option<OnEvict<K, V>>,
+}
+
+/// The entries of an `LruCache`, from the most recently used to the least.
+pub struct LruIter<'a, K, V>(Iter<'a, (K, V)>);
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    // A cache holding up to `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: LinkedList::new(),
+            handles: HashMap::with_capacity(capacity),
+            capacity,
+            on_evict: None,
+        }
+    }
+
+    // Calls `f` with every entry evicted to make room for a new one,
+    // but not with those removed with `pop_lru` or `remove`.
+    pub fn on_evict<F: FnMut(K, V) + Send + 'static>(&mut self, f: F) {
+        self.on_evict = Some(Box::new(f));
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.handles.contains_key(key)
+    }
+
+    // Returns the value of the key, making it the most recently used.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_mut(key).map(|v| &*v)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = *self.handles.get(key)?;
+        Self::move_to_front(&mut self.entries, handle);
+        self.entries.front_mut().map(|(_, v)| v)
+    }
+
+    // Same as `get`, without making the key the most recently used.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = *self.handles.get(key)?;
+        // see `LruCache`
+        let (_, v) = unsafe { self.entries.get(handle) };
+        Some(v)
+    }
+
+    // Sets the value of the key, making it the most recently used.
+    // The least recently used entry is evicted when the cache is full.
+    //
+    // Returns the previous value of the key, if any.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&handle) = self.handles.get(&key) {
+            Self::move_to_front(&mut self.entries, handle);
+            let (_, v) = self.entries.front_mut().unwrap();
+            return Some(std::mem::replace(v, value));
+        }
+
+        // no room at all
+        if self.capacity == 0 {
+            self.evict(key, value);
+            return None;
+        }
+        if self.len() >= self.capacity {
+            if let Some((key, value)) = self.pop_lru() {
+                self.evict(key, value);
+            }
+        }
+
+        self.entries.push_front((key.clone(), value));
+        self.handles
+            .insert(key, self.entries.front_handle().unwrap());
+        None
+    }
+
+    // Removes the least recently used entry.
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let (key, value) = self.entries.pop_back()?;
+        self.handles.remove(&key);
+        Some((key, value))
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let handle = self.handles.remove(key)?;
+        // see `LruCache`
+        let mut cursor = unsafe { self.entries.cursor_at(handle) };
+        cursor.take().map(|(_, v)| v)
+    }
+
+    // Moves the entry to the front by relinking its node,
+    // which neither allocates nor changes its handle.
+    fn move_to_front(entries: &mut LinkedList<(K, V)>, handle: Handle<(K, V)>) {
+        let node = handle.0;
+        if entries.head == Some(node) {
+            return;
+        }
+        // see `LruCache`, the node is in the list and then on its own
+        unsafe {
+            entries.unlink(node, node, 1);
+            entries.link(None, entries.head, node, node, 1);
+        }
+    }
+
+    fn evict(&mut self, key: K, value: V) {
+        if let Some(on_evict) = &mut self.on_evict {
+            on_evict(key, value);
+        }
+    }
+
+    /// Return an iterator from the most recently used entry to the least,
+    /// which does not change how recently any of them was used
+    pub fn iter(&self) -> LruIter<'_, K, V> {
+        LruIter(self.entries.iter())
+    }
+}
+
+impl<'a, K, V> Iterator for LruIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        self.0.next().map(|(k, v)| (k, v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for LruIter<'a, K, V> {
+    fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+        self.0.next_back().map(|(k, v)| (k, v))
+    }
+}
+
+impl<K, V> ExactSizeIterator for LruIter<'_, K, V> {}
@@ -3,6 +3,9 @@
 //! They are useful for the test framework, but the implementation is trivial.
 //! We supply them to reduce work both for you and the mentors.
 use crate::{Cursor, LinkedList};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 
 impl<T> LinkedList<T> {
     pub fn push_back(&mut self, element: T) {
@@ -21,12 +24,8 @@ impl<T> LinkedList<T> {
         self.cursor_front().take()
     }
 
-    pub fn front(&self) -> Option<&T> {
-        self.iter().next()
-    }
-
-    pub fn back(&self) -> Option<&T> {
-        self.iter().last()
+    pub fn append(&mut self, other: &mut Self) {
+        self.cursor_back().splice_after(std::mem::take(other));
     }
 }
 
@@ -36,10 +35,71 @@ impl<T> std::iter::FromIterator<T> for LinkedList<T> {
         I: IntoIterator<Item = T>,
     {
         let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
         for elem in iter {
-            list.push_back(elem);
+            self.push_back(elem);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a> Extend<&'a T> for LinkedList<T> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = &'a T>,
+    {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T: Clone> Clone for LinkedList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for LinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for LinkedList<T> {}
+
+impl<T: PartialOrd> PartialOrd for LinkedList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord> Ord for LinkedList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other)
+    }
+}
+
+// the length goes first, so that lists of lists hash
+// differently however their elements are split between them
+impl<T: Hash> Hash for LinkedList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for elem in self {
+            elem.hash(state);
         }
-        list
     }
 }
 
@@ -0,0 +1,156 @@
+mod common;
+
+use common::assert_list_eq;
+use doubly_linked_list::*;
+
+// addresses of the values, front to back
+fn addresses<T>(list: &LinkedList<T>) -> Vec<*const T> {
+    list.iter().map(|v| v as *const T).collect()
+}
+
+#[test]
+fn sort() {
+    let mut list: LinkedList<i32> = [5, 3, 9, 1, 1, 8, 0, 7].iter().copied().collect();
+    list.sort();
+    assert_list_eq(&mut list, &[0, 1, 1, 3, 5, 7, 8, 9]);
+
+    let mut empty: LinkedList<i32> = LinkedList::new();
+    empty.sort();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn sort_is_stable() {
+    let values = [(2, 'a'), (1, 'b'), (2, 'c'), (0, 'd'), (1, 'e'), (2, 'f')];
+    let mut list: LinkedList<_> = values.iter().copied().collect();
+    list.sort_by_key(|&(key, _)| key);
+    assert_list_eq(
+        &mut list,
+        &[(0, 'd'), (1, 'b'), (1, 'e'), (2, 'a'), (2, 'c'), (2, 'f')],
+    );
+
+    list.sort_by(|a, b| b.0.cmp(&a.0));
+    assert_list_eq(
+        &mut list,
+        &[(2, 'a'), (2, 'c'), (2, 'f'), (1, 'b'), (1, 'e'), (0, 'd')],
+    );
+}
+
+#[test]
+fn sort_keeps_the_values_in_place() {
+    let mut list: LinkedList<_> = (0..100).map(|n| (n * 37) % 100).collect();
+    let mut before: Vec<_> = list
+        .iter()
+        .zip(addresses(&list))
+        .map(|(&v, a)| (v, a))
+        .collect();
+    list.sort();
+    before.sort();
+    assert_eq!(
+        addresses(&list),
+        before.iter().map(|&(_, a)| a).collect::<Vec<_>>()
+    );
+    assert!((0..100).eq(list.iter().copied()));
+}
+
+#[test]
+fn sort_a_large_list() {
+    let len = if cfg!(miri) { 1_000 } else { 100_000 };
+    let mut list: LinkedList<u64> = (0..len).map(|n| (n * 7919) % len).collect();
+    list.sort();
+    assert!((0..len).eq(list.iter().copied()));
+}
+
+#[test]
+fn merge() {
+    let mut list: LinkedList<_> = [1, 3, 5, 7].iter().copied().collect();
+    list.merge([0, 3, 4, 8, 9].iter().copied().collect());
+    assert_list_eq(&mut list, &[0, 1, 3, 3, 4, 5, 7, 8, 9]);
+
+    let mut empty = LinkedList::new();
+    empty.merge(list);
+    assert_eq!(empty.len(), 9);
+    empty.merge(LinkedList::new());
+    assert_eq!(empty.len(), 9);
+}
+
+#[test]
+fn merge_puts_this_list_first_among_equals() {
+    let mut list: LinkedList<_> = [(1, 'a'), (2, 'a')].iter().copied().collect();
+    let other: LinkedList<_> = [(1, 'b'), (2, 'b'), (3, 'b')].iter().copied().collect();
+    list.merge_by(other, |a, b| a.0.cmp(&b.0));
+    assert_list_eq(
+        &mut list,
+        &[(1, 'a'), (1, 'b'), (2, 'a'), (2, 'b'), (3, 'b')],
+    );
+}
+
+#[test]
+fn retain() {
+    let mut list: LinkedList<_> = (0..10).collect();
+    list.retain(|&n| n % 3 == 0);
+    assert_list_eq(&mut list, &[0, 3, 6, 9]);
+    list.retain(|&n| n > 3);
+    assert_list_eq(&mut list, &[6, 9]);
+    list.retain(|_| false);
+    assert_list_eq(&mut list, &[]);
+}
+
+#[test]
+fn retain_calls_each_element_once() {
+    let mut list: LinkedList<_> = (0..6).collect();
+    let mut seen = Vec::new();
+    list.retain_mut(|n| {
+        seen.push(*n);
+        *n *= 10;
+        *n < 30 || *n == 50
+    });
+    assert_eq!(seen, vec![0, 1, 2, 3, 4, 5]);
+    assert_list_eq(&mut list, &[0, 10, 20, 50]);
+}
+
+#[test]
+fn retain_keeps_the_values_in_place() {
+    let mut list: LinkedList<_> = (0..10).collect();
+    let kept: Vec<_> = addresses(&list).into_iter().step_by(2).collect();
+    list.retain(|n| n % 2 == 0);
+    assert_eq!(addresses(&list), kept);
+}
+
+#[test]
+fn dedup() {
+    let mut list: LinkedList<_> = [1, 1, 2, 3, 3, 3, 1, 4, 4].iter().copied().collect();
+    list.dedup();
+    assert_list_eq(&mut list, &[1, 2, 3, 1, 4]);
+
+    let mut list: LinkedList<_> = [10, 11, 20, 21, 22, 30].iter().copied().collect();
+    list.dedup_by_key(|n| *n / 10);
+    assert_list_eq(&mut list, &[10, 20, 30]);
+
+    let mut list: LinkedList<_> = [1, 2, 4, 5, 7].iter().copied().collect();
+    list.dedup_by(|a, b| *a - *b == 1);
+    assert_list_eq(&mut list, &[1, 4, 7]);
+}
+
+#[test]
+fn drain_filter() {
+    let mut list: LinkedList<_> = (0..10).collect();
+    let evens: Vec<_> = list.drain_filter(|n| *n % 2 == 0).collect();
+    assert_eq!(evens, vec![0, 2, 4, 6, 8]);
+    assert_list_eq(&mut list, &[1, 3, 5, 7, 9]);
+
+    let all: Vec<_> = list.drain_filter(|_| true).collect();
+    assert_eq!(all, vec![1, 3, 5, 7, 9]);
+    assert!(list.is_empty());
+}
+
+#[test]
+fn drain_filter_dropped_early_keeps_the_rest() {
+    let mut list: LinkedList<_> = (0..10).collect();
+    {
+        let mut drain = list.drain_filter(|n| *n > 2);
+        assert_eq!(drain.next(), Some(3));
+        assert_eq!(drain.next(), Some(4));
+    }
+    assert_list_eq(&mut list, &[0, 1, 2, 5, 6, 7, 8, 9]);
+}
@@ -0,0 +1,175 @@
+use doubly_linked_list::*;
+
+#[test]
+fn push_pop_at_both_ends() {
+    let mut list = ArenaList::new();
+    assert!(list.is_empty());
+    list.push_back(1);
+    list.push_back(2);
+    list.push_front(0);
+    assert_eq!(list.len(), 3);
+    assert_eq!(list.front(), Some(&0));
+    assert_eq!(list.back(), Some(&2));
+
+    assert_eq!(list.pop_front(), Some(0));
+    assert_eq!(list.pop_back(), Some(2));
+    assert_eq!(list.pop_back(), Some(1));
+    assert_eq!(list.pop_back(), None);
+    assert!(list.is_empty());
+}
+
+#[test]
+fn iter_from_both_ends() {
+    let list = (0..6).collect::<ArenaList<_>>();
+    assert_eq!(list.iter().len(), 6);
+    assert!((0..6).eq(list.iter().cloned()));
+    assert!((0..6).rev().eq(list.iter().rev().cloned()));
+}
+
+#[test]
+fn handles_stay_valid_while_the_list_changes() {
+    let mut list = ArenaList::new();
+    let handles: Vec<_> = (0..5).map(|n| list.push_back(n)).collect();
+    list.push_front(-1);
+    assert_eq!(list.pop_back(), Some(4));
+
+    assert_eq!(list.get(handles[2]), Some(&2));
+    *list.get_mut(handles[0]).unwrap() = 10;
+    assert_eq!(list.get(handles[4]), None);
+    assert!([-1, 10, 1, 2, 3].iter().eq(list.iter()));
+}
+
+#[test]
+fn remove_by_handle() {
+    let mut list = ArenaList::new();
+    let handles: Vec<_> = (0..5).map(|n| list.push_back(n)).collect();
+    assert_eq!(list.remove(handles[2]), Some(2));
+    assert_eq!(list.remove(handles[0]), Some(0));
+    assert_eq!(list.remove(handles[4]), Some(4));
+    assert_eq!(list.remove(handles[4]), None);
+    assert_eq!(list.len(), 2);
+    assert!([1, 3].iter().eq(list.iter()));
+    assert!([3, 1].iter().eq(list.iter().rev()));
+}
+
+#[test]
+fn stale_handles_do_not_resolve_to_reused_slots() {
+    let mut list = ArenaList::new();
+    let old = list.push_back(1);
+    assert_eq!(list.remove(old), Some(1));
+    let new = list.push_back(2);
+    assert_ne!(old, new);
+    assert_eq!(list.get(old), None);
+    assert!(!list.move_to_front(old));
+    assert_eq!(list.get(new), Some(&2));
+}
+
+#[test]
+fn move_to_front_and_back() {
+    let mut list = ArenaList::new();
+    let handles: Vec<_> = (0..4).map(|n| list.push_back(n)).collect();
+    assert!(list.move_to_front(handles[2]));
+    assert!([2, 0, 1, 3].iter().eq(list.iter()));
+    assert!(list.move_to_front(handles[2]));
+    assert!(list.move_to_back(handles[0]));
+    assert!([2, 1, 3, 0].iter().eq(list.iter()));
+    assert!([0, 3, 1, 2].iter().eq(list.iter().rev()));
+    assert_eq!(list.front_handle(), Some(handles[2]));
+    assert_eq!(list.back_handle(), Some(handles[0]));
+    assert_eq!(list.len(), 4);
+}
+
+#[test]
+fn cursor_works_like_the_boxed_one() {
+    let mut list = (0..10).collect::<ArenaList<_>>();
+    {
+        let mut cursor = list.cursor_front();
+        assert!(cursor.seek_forward(4));
+        for n in (0..10).rev() {
+            cursor.insert_after(n);
+        }
+        cursor.insert_before(100);
+        assert_eq!(cursor.take(), Some(4));
+        assert_eq!(cursor.peek_mut(), Some(&mut 0));
+    }
+    let expected = (0..4).chain(Some(100)).chain(0..10).chain(5..10);
+    assert!(expected.eq(list.iter().cloned()));
+
+    let mut cursor = list.cursor_back();
+    let mut taken = Vec::new();
+    while let Some(n) = cursor.take() {
+        taken.push(n);
+    }
+    assert_eq!(taken.len(), 20);
+    assert!(list.is_empty());
+}
+
+#[test]
+fn cursor_insert_into_an_empty_list() {
+    let mut list = ArenaList::new();
+    let mut cursor = list.cursor_front();
+    cursor.insert_before(1);
+    assert_eq!(cursor.peek_mut(), Some(&mut 1));
+    cursor.insert_after(2);
+    assert!([1, 2].iter().eq(list.iter()));
+}
+
+#[test]
+fn cursor_at_a_handle() {
+    let mut list = ArenaList::new();
+    let handles: Vec<_> = (0..5).map(|n| list.push_back(n)).collect();
+    let mut cursor = list.cursor_at(handles[3]).unwrap();
+    assert_eq!(cursor.prev(), Some(&mut 2));
+    assert_eq!(cursor.handle(), Some(handles[2]));
+    assert_eq!(cursor.take(), Some(2));
+    assert!(list.cursor_at(handles[2]).is_none());
+}
+
+#[test]
+fn cursor_split_and_splice() {
+    let mut list = ArenaList::new();
+    let handles: Vec<_> = (0..6).map(|n| list.push_back(n)).collect();
+
+    let mut cursor = list.cursor_at(handles[2]).unwrap();
+    let after = cursor.split_after();
+    let before = cursor.split_before();
+    assert!([0, 1].iter().eq(before.iter()));
+    assert!([3, 4, 5].iter().eq(after.iter()));
+    assert_eq!(cursor.peek_mut(), Some(&mut 2));
+
+    cursor.splice_before(after);
+    cursor.splice_after(before);
+    assert_eq!(cursor.peek_mut(), Some(&mut 2));
+    assert_eq!(cursor.next(), Some(&mut 0));
+    assert!([3, 4, 5, 2, 0, 1].iter().eq(list.iter()));
+    assert!([1, 0, 2, 5, 4, 3].iter().eq(list.iter().rev()));
+    // the moved elements are not where their handles point anymore
+    assert_eq!(list.get(handles[2]), Some(&2));
+    assert_eq!(list.get(handles[0]), None);
+
+    // past the ends
+    let mut cursor = list.cursor_front();
+    cursor.prev();
+    cursor.splice_after((10..12).collect());
+    cursor.splice_before((20..22).collect());
+    assert_eq!(cursor.peek_mut(), None);
+    assert!([10, 11, 3, 4, 5, 2, 0, 1, 20, 21].iter().eq(list.iter()));
+    let all = list.cursor_back().split_after();
+    assert!(all.is_empty());
+    let mut cursor = list.cursor_front();
+    cursor.prev();
+    assert_eq!(cursor.split_before().len(), 10);
+    assert!(list.is_empty());
+}
+
+#[test]
+fn slots_are_reused() {
+    let mut list = ArenaList::with_capacity(4);
+    for n in 0..1000 {
+        list.push_back(n);
+        if list.len() > 3 {
+            list.pop_front();
+        }
+    }
+    assert!([997, 998, 999].iter().eq(list.iter()));
+}
@@ -0,0 +1,26 @@
+// Helpers shared by the test files, each one pulls them in with `mod common;`.
+
+use doubly_linked_list::LinkedList;
+use std::fmt::Debug;
+
+// checks the length, the ends and the links both ways against `expected`,
+// walking the list with the iterators and back again with a cursor
+pub fn assert_list_eq<T: PartialEq + Debug>(list: &mut LinkedList<T>, expected: &[T]) {
+    assert_eq!(list.len(), expected.len());
+    assert_eq!(list.is_empty(), expected.is_empty());
+    assert_eq!(list.front(), expected.first());
+    assert_eq!(list.back(), expected.last());
+    assert!(expected.iter().eq(list.iter()));
+    assert!(expected.iter().rev().eq(list.iter().rev()));
+    assert!(expected.iter().eq(list.iter_mut().map(|v| &*v)));
+
+    let mut backwards = expected.iter().rev();
+    let mut cursor = list.cursor_back();
+    if let Some(v) = cursor.peek_mut() {
+        assert_eq!(Some(&*v), backwards.next());
+    }
+    while let Some(v) = cursor.prev() {
+        assert_eq!(Some(&*v), backwards.next());
+    }
+    assert_eq!(backwards.next(), None);
+}
@@ -1,3 +1,6 @@
+mod common;
+
+use common::assert_list_eq;
 use doubly_linked_list::*;
 
 #[test]
@@ -132,6 +135,79 @@ fn iter() {
     }
 }
 
+#[test]
+fn iter_from_both_ends() {
+    let list = (0..6).collect::<LinkedList<_>>();
+    let mut iter = list.iter();
+    assert_eq!(iter.len(), 6);
+    assert_eq!(iter.next(), Some(&0));
+    assert_eq!(iter.next_back(), Some(&5));
+    assert_eq!(iter.next_back(), Some(&4));
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next(), Some(&1));
+    assert_eq!(iter.next(), Some(&2));
+    assert_eq!(iter.next_back(), Some(&3));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    assert!((0..6).rev().eq(list.iter().rev().cloned()));
+}
+
+#[test]
+fn iter_mut() {
+    let mut list = (0..6).collect::<LinkedList<_>>();
+    for v in list.iter_mut() {
+        *v *= 10;
+    }
+    let mut iter = list.iter_mut();
+    *iter.next_back().unwrap() += 1;
+    *iter.next().unwrap() += 2;
+    assert_eq!(iter.len(), 4);
+    for v in &mut list {
+        *v += 1;
+    }
+    assert!([3, 11, 21, 31, 41, 52].iter().eq(&list));
+}
+
+#[test]
+fn into_iter() {
+    let list = (0..6).collect::<LinkedList<_>>();
+    let mut iter = list.into_iter();
+    assert_eq!(iter.len(), 6);
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next_back(), Some(5));
+    assert_eq!(iter.collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+
+    let list = (0..3).map(|n| n.to_string()).collect::<LinkedList<_>>();
+    let mut seen = Vec::new();
+    for s in list {
+        seen.push(s);
+    }
+    assert_eq!(seen, vec!["0", "1", "2"]);
+}
+
+#[test]
+fn into_iter_drops_the_rest() {
+    use std::rc::Rc;
+    let value = Rc::new(());
+    let list = std::iter::repeat_with(|| Rc::clone(&value))
+        .take(5)
+        .collect::<LinkedList<_>>();
+    let mut iter = list.into_iter();
+    iter.next();
+    drop(iter);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn extend() {
+    let mut list = (0..2).collect::<LinkedList<_>>();
+    list.extend(2..4);
+    list.extend(&[4, 5]);
+    assert_eq!(list.len(), 6);
+    assert!((0..6).eq(list.iter().cloned()));
+}
+
 // ———————————————————————————————————————————————————————————
 // Tests for Step 3: full cursor functionality
 // ———————————————————————————————————————————————————————————
@@ -228,6 +304,169 @@ fn cursor_take() {
     }
 }
 
+// length and ends ——————————————————————————————————————————
+#[test]
+fn len_follows_every_cursor_change() {
+    let mut list = (0..10).collect::<LinkedList<_>>();
+    assert_eq!(list.len(), 10);
+
+    let mut cursor = list.cursor_front();
+    assert!(cursor.seek_forward(3));
+    cursor.insert_before(100);
+    cursor.insert_after(200);
+    assert_eq!(cursor.take(), Some(3));
+    assert_eq!(list.len(), 11);
+
+    let mut cursor = list.cursor_back();
+    while cursor.take().is_some() {}
+    assert_eq!(list.len(), 0);
+    assert!(list.is_empty());
+}
+
+#[test]
+fn front_and_back() {
+    let mut list: LinkedList<i32> = LinkedList::new();
+    assert_eq!(list.front(), None);
+    assert_eq!(list.back(), None);
+    assert_eq!(list.front_mut(), None);
+    assert_eq!(list.back_mut(), None);
+
+    list.push_back(1);
+    assert_eq!(list.front(), Some(&1));
+    assert_eq!(list.back(), Some(&1));
+
+    list.push_back(2);
+    list.push_front(0);
+    assert_eq!(list.front(), Some(&0));
+    assert_eq!(list.back(), Some(&2));
+
+    *list.front_mut().unwrap() = 10;
+    *list.back_mut().unwrap() = 20;
+    assert!([10, 1, 20].iter().eq(list.iter()));
+
+    list.pop_back();
+    assert_eq!(list.back(), Some(&1));
+    list.pop_front();
+    assert_eq!(list.front(), Some(&1));
+}
+
+// split, splice and append —————————————————————————————————
+
+#[test]
+fn cursor_split_after() {
+    let mut list = (0..10).collect::<LinkedList<_>>();
+    let mut cursor = list.cursor_front();
+    assert!(cursor.seek_forward(3));
+    let mut rest = cursor.split_after();
+    assert_eq!(cursor.peek_mut(), Some(&mut 3));
+    cursor.insert_after(100);
+
+    assert_list_eq(&mut list, &[0, 1, 2, 3, 100]);
+    assert_list_eq(&mut rest, &[4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn cursor_split_before() {
+    let mut list = (0..10).collect::<LinkedList<_>>();
+    let mut cursor = list.cursor_back();
+    assert!(cursor.seek_backward(3));
+    let mut first = cursor.split_before();
+    assert_eq!(cursor.peek_mut(), Some(&mut 6));
+    // the cursor is at the front now
+    assert_eq!(cursor.prev(), None);
+
+    assert_list_eq(&mut list, &[6, 7, 8, 9]);
+    assert_list_eq(&mut first, &[0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn cursor_split_at_the_ends() {
+    let mut list = (0..3).collect::<LinkedList<_>>();
+    let mut nothing = list.cursor_back().split_after();
+    assert_list_eq(&mut nothing, &[]);
+    let mut nothing = list.cursor_front().split_before();
+    assert_list_eq(&mut nothing, &[]);
+
+    let mut cursor = list.cursor_back();
+    cursor.next();
+    let mut everything = cursor.split_after();
+    assert_list_eq(&mut everything, &[0, 1, 2]);
+    assert_list_eq(&mut list, &[]);
+
+    let mut empty: LinkedList<i32> = LinkedList::new();
+    assert!(empty.cursor_front().split_before().is_empty());
+}
+
+#[test]
+fn cursor_split_keeps_counting_positions() {
+    let mut list = (0..10).collect::<LinkedList<_>>();
+    let mut cursor = list.cursor_front();
+    assert!(cursor.seek_forward(2));
+    cursor.insert_before(100);
+    cursor.insert_after(200);
+    assert_eq!(cursor.take(), Some(2));
+    assert_eq!(cursor.prev(), Some(&mut 100));
+    let mut rest = cursor.split_after();
+
+    assert_list_eq(&mut list, &[0, 1, 100]);
+    assert_list_eq(&mut rest, &[200, 3, 4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn cursor_splice_after() {
+    let mut list = (0..5).collect::<LinkedList<_>>();
+    let mut cursor = list.cursor_front();
+    assert!(cursor.seek_forward(1));
+    cursor.splice_after((10..13).collect());
+    assert_eq!(cursor.peek_mut(), Some(&mut 1));
+    cursor.splice_after(LinkedList::new());
+    assert_list_eq(&mut list, &[0, 1, 10, 11, 12, 2, 3, 4]);
+
+    list.cursor_back().splice_after((20..22).collect());
+    assert_list_eq(&mut list, &[0, 1, 10, 11, 12, 2, 3, 4, 20, 21]);
+}
+
+#[test]
+fn cursor_splice_before() {
+    let mut list = (0..5).collect::<LinkedList<_>>();
+    let mut cursor = list.cursor_back();
+    assert!(cursor.seek_backward(1));
+    cursor.splice_before((10..13).collect());
+    assert_eq!(cursor.peek_mut(), Some(&mut 3));
+    let mut rest = cursor.split_before();
+    assert_list_eq(&mut rest, &[0, 1, 2, 10, 11, 12]);
+    assert_list_eq(&mut list, &[3, 4]);
+
+    list.cursor_front().splice_before((20..22).collect());
+    assert_list_eq(&mut list, &[20, 21, 3, 4]);
+}
+
+#[test]
+fn cursor_splice_into_an_empty_list() {
+    let mut list = LinkedList::new();
+    list.cursor_front().splice_after((0..2).collect());
+    assert_list_eq(&mut list, &[0, 1]);
+
+    let mut list = LinkedList::new();
+    list.cursor_back().splice_before((0..2).collect());
+    assert_list_eq(&mut list, &[0, 1]);
+}
+
+#[test]
+fn append() {
+    let mut list = (0..3).collect::<LinkedList<_>>();
+    let mut other = (3..6).collect::<LinkedList<_>>();
+    list.append(&mut other);
+    assert_list_eq(&mut list, &[0, 1, 2, 3, 4, 5]);
+    assert_list_eq(&mut other, &[]);
+
+    other.append(&mut list);
+    assert_list_eq(&mut other, &[0, 1, 2, 3, 4, 5]);
+    assert_list_eq(&mut list, &[]);
+    list.append(&mut LinkedList::new());
+    assert_list_eq(&mut list, &[]);
+}
+
 // ———————————————————————————————————————————————————————————
 // Tests for Step 4: clean-up via `Drop`
 // ———————————————————————————————————————————————————————————
@@ -264,6 +503,7 @@ fn drop_no_double_frees() {
 }
 
 #[test]
+#[cfg_attr(miri, ignore)]
 fn drop_large_list() {
     drop((0..2_000_000).collect::<LinkedList<i32>>());
 }
@@ -279,7 +519,9 @@ fn drop_large_list() {
 
 #[test]
 fn advanced_linked_list_is_send_sync() {
+    #[allow(dead_code)]
     trait AssertSend: Send {}
+    #[allow(dead_code)]
     trait AssertSync: Sync {}
 
     impl<T: Send> AssertSend for LinkedList<T> {}
@@ -0,0 +1,144 @@
+use doubly_linked_list::*;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn get_and_put() {
+    let mut cache = LruCache::new(2);
+    assert!(cache.is_empty());
+    assert_eq!(cache.put("a", 1), None);
+    assert_eq!(cache.put("b", 2), None);
+    assert_eq!(cache.get("a"), Some(&1));
+    assert_eq!(cache.get("c"), None);
+    assert_eq!(cache.put("a", 10), Some(1));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.peek("a"), Some(&10));
+}
+
+#[test]
+fn evicts_the_least_recently_used() {
+    let mut cache = LruCache::new(2);
+    cache.put(1, "one");
+    cache.put(2, "two");
+    cache.get(&1);
+    cache.put(3, "three");
+    assert!(cache.contains(&1));
+    assert!(!cache.contains(&2));
+    assert!(cache.contains(&3));
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn peek_does_not_change_the_order() {
+    let mut cache = LruCache::new(2);
+    cache.put(1, 1);
+    cache.put(2, 2);
+    assert_eq!(cache.peek(&1), Some(&1));
+    cache.put(3, 3);
+    assert!(!cache.contains(&1));
+}
+
+#[test]
+fn get_mut_updates_in_place() {
+    let mut cache = LruCache::new(2);
+    cache.put("a".to_string(), vec![1]);
+    cache.get_mut("a").unwrap().push(2);
+    assert_eq!(cache.get("a"), Some(&vec![1, 2]));
+}
+
+#[test]
+fn eviction_callback() {
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+    let mut cache = LruCache::new(2);
+    {
+        let evicted = Arc::clone(&evicted);
+        cache.on_evict(move |k, v| evicted.lock().unwrap().push((k, v)));
+    }
+    for n in 0..5 {
+        cache.put(n, n * 10);
+    }
+    // replacing a value and removing entries is no eviction
+    cache.put(4, 400);
+    cache.remove(&4);
+    cache.pop_lru();
+    assert_eq!(*evicted.lock().unwrap(), vec![(0, 0), (1, 10), (2, 20)]);
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn zero_capacity_evicts_right_away() {
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+    let mut cache = LruCache::new(0);
+    {
+        let evicted = Arc::clone(&evicted);
+        cache.on_evict(move |k, v| evicted.lock().unwrap().push((k, v)));
+    }
+    assert_eq!(cache.put(1, 1), None);
+    assert!(cache.is_empty());
+    assert_eq!(cache.get(&1), None);
+    assert_eq!(*evicted.lock().unwrap(), vec![(1, 1)]);
+}
+
+#[test]
+fn pop_lru_and_remove() {
+    let mut cache = LruCache::new(3);
+    cache.put(1, 'a');
+    cache.put(2, 'b');
+    cache.put(3, 'c');
+    cache.get(&1);
+    assert_eq!(cache.pop_lru(), Some((2, 'b')));
+    assert_eq!(cache.remove(&1), Some('a'));
+    assert_eq!(cache.remove(&1), None);
+    assert_eq!(cache.pop_lru(), Some((3, 'c')));
+    assert_eq!(cache.pop_lru(), None);
+}
+
+#[test]
+fn iter_in_recency_order() {
+    let mut cache = LruCache::new(4);
+    for n in 0..4 {
+        cache.put(n, n);
+    }
+    cache.get(&1);
+    cache.put(2, 20);
+    let keys: Vec<_> = cache.iter().map(|(k, _)| *k).collect();
+    assert_eq!(keys, vec![2, 1, 3, 0]);
+    let values: Vec<_> = cache.iter().rev().map(|(_, v)| *v).collect();
+    assert_eq!(values, vec![0, 3, 1, 20]);
+    assert_eq!(cache.iter().len(), 4);
+    assert_eq!(cache.capacity(), 4);
+}
+
+#[test]
+fn remove_from_the_middle_keeps_the_order() {
+    let mut cache = LruCache::new(4);
+    for i in 0..4 {
+        cache.put(i, i * 10);
+    }
+    assert_eq!(cache.remove(&2), Some(20));
+    assert_eq!(cache.remove(&2), None);
+    assert_eq!(cache.get(&0), Some(&0));
+    *cache.get_mut(&1).unwrap() += 1;
+    assert_eq!(
+        cache.iter().map(|(&k, &v)| (k, v)).collect::<Vec<_>>(),
+        [(1, 11), (0, 0), (3, 30)]
+    );
+    assert_eq!(cache.pop_lru(), Some((3, 30)));
+    assert_eq!(cache.peek(&0), Some(&0));
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn lru_cache_is_send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<LruCache<u32, u32>>();
+
+    let cache = Arc::new(Mutex::new(LruCache::new(2)));
+    let other = Arc::clone(&cache);
+    std::thread::spawn(move || other.lock().unwrap().put(1, String::from("one")))
+        .join()
+        .unwrap();
+    assert_eq!(
+        cache.lock().unwrap().get(&1).map(String::as_str),
+        Some("one")
+    );
+}
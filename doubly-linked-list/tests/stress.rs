@@ -0,0 +1,250 @@
+// Random sequences of cursor operations checked against a `Vec`.
+// Meant to be run under Miri as well, to catch any undefined behaviour
+// or leak on the way, hence the smaller sizes there, see `miri.sh`.
+
+mod common;
+
+use common::assert_list_eq;
+use doubly_linked_list::*;
+
+const ROUNDS: usize = if cfg!(miri) { 40 } else { 500 };
+const STEPS: usize = if cfg!(miri) { 20 } else { 50 };
+
+// xorshift, good enough to shuffle the operations around
+struct Random(u64);
+
+impl Random {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+// the list, as a `Vec`, and the position of a cursor in it
+struct Model {
+    values: Vec<i32>,
+    position: Option<usize>,
+}
+
+fn run(seed: u64) {
+    let mut random = Random(seed);
+    let mut next_value = 0;
+    let mut list = LinkedList::new();
+    let mut model = Model {
+        values: Vec::new(),
+        position: None,
+    };
+    // lists split off, waiting to be spliced back in
+    let mut spare: Vec<(LinkedList<i32>, Vec<i32>)> = Vec::new();
+
+    for _ in 0..ROUNDS {
+        let mut cursor = if random.below(2) == 0 {
+            model.position = if model.values.is_empty() {
+                None
+            } else {
+                Some(0)
+            };
+            list.cursor_front()
+        } else {
+            model.position = model.values.len().checked_sub(1);
+            list.cursor_back()
+        };
+
+        for _ in 0..STEPS {
+            let len = model.values.len();
+            match random.below(10) {
+                0 => {
+                    cursor.next();
+                    model.position = model.position.map(|i| i + 1).filter(|&i| i < len);
+                }
+                1 => {
+                    cursor.prev();
+                    model.position = model.position.and_then(|i| i.checked_sub(1));
+                }
+                2 => {
+                    let expected = model.position.map(|i| {
+                        let value = model.values.remove(i);
+                        model.position = if i < len - 1 {
+                            Some(i)
+                        } else {
+                            i.checked_sub(1)
+                        };
+                        value
+                    });
+                    assert_eq!(cursor.take(), expected);
+                }
+                3 => {
+                    next_value += 1;
+                    cursor.insert_after(next_value);
+                    match model.position {
+                        Some(i) => model.values.insert(i + 1, next_value),
+                        None => {
+                            model.values.insert(0, next_value);
+                            if len == 0 {
+                                model.position = Some(0);
+                            }
+                        }
+                    }
+                }
+                4 => {
+                    next_value += 1;
+                    cursor.insert_before(next_value);
+                    match model.position {
+                        Some(i) => {
+                            model.values.insert(i, next_value);
+                            model.position = Some(i + 1);
+                        }
+                        None => {
+                            model.values.push(next_value);
+                            if len == 0 {
+                                model.position = Some(0);
+                            }
+                        }
+                    }
+                }
+                5 => {
+                    let list = cursor.split_after();
+                    let values = match model.position {
+                        Some(i) => model.values.split_off(i + 1),
+                        None => std::mem::take(&mut model.values),
+                    };
+                    spare.push((list, values));
+                }
+                6 => {
+                    let list = cursor.split_before();
+                    let values = match model.position {
+                        Some(i) => {
+                            model.position = Some(0);
+                            model.values.drain(..i).collect()
+                        }
+                        None => std::mem::take(&mut model.values),
+                    };
+                    spare.push((list, values));
+                }
+                7 | 8 => {
+                    let (list, values) = match spare.pop() {
+                        Some(spare) => spare,
+                        None => continue,
+                    };
+                    let added = values.len();
+                    let at = match model.position {
+                        Some(i) if random.below(2) == 0 => {
+                            cursor.splice_after(list);
+                            i + 1
+                        }
+                        Some(i) => {
+                            cursor.splice_before(list);
+                            model.position = Some(i + added);
+                            i
+                        }
+                        None if random.below(2) == 0 => {
+                            cursor.splice_after(list);
+                            0
+                        }
+                        None => {
+                            cursor.splice_before(list);
+                            len
+                        }
+                    };
+                    model.values.splice(at..at, values);
+                }
+                _ => {
+                    if let Some(value) = cursor.peek_mut() {
+                        *value *= -1;
+                    }
+                    if let Some(i) = model.position {
+                        model.values[i] *= -1;
+                    }
+                }
+            }
+            assert_eq!(
+                cursor.peek_mut().copied(),
+                model.position.map(|i| model.values[i])
+            );
+        }
+
+        assert_list_eq(&mut list, &model.values);
+        for (list, values) in &mut spare {
+            assert_list_eq(list, values);
+        }
+    }
+}
+
+#[test]
+fn random_cursor_operations() {
+    for seed in 1..=4 {
+        run(seed);
+    }
+}
+
+#[test]
+fn drop_in_the_middle_of_operations() {
+    use std::rc::Rc;
+    let value = Rc::new(());
+    let mut list = std::iter::repeat_with(|| Rc::clone(&value))
+        .take(10)
+        .collect::<LinkedList<_>>();
+    let mut cursor = list.cursor_front();
+    cursor.seek_forward(3);
+    let first = cursor.split_before();
+    cursor.next();
+    let rest = cursor.split_after();
+    cursor.take();
+    drop(first);
+    drop(rest);
+    assert_eq!(list.len(), 1);
+    drop(list);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn send_to_another_thread() {
+    let list = (0..10).collect::<LinkedList<_>>();
+    let list = std::thread::spawn(move || list.into_iter().rev().collect::<LinkedList<_>>())
+        .join()
+        .unwrap();
+    assert!((0..10).rev().eq(list));
+}
+
+// The LRU cache relinks and takes out nodes through their handles,
+// checked against a `Vec` from the most recently used entry to the least.
+#[test]
+fn random_lru_cache_operations() {
+    const CAPACITY: usize = 8;
+    for seed in 1..=4 {
+        let mut random = Random(seed);
+        let mut cache = LruCache::new(CAPACITY);
+        let mut model: Vec<(usize, usize)> = Vec::new();
+        for value in 0..ROUNDS {
+            let key = random.below(2 * CAPACITY);
+            let found = model.iter().position(|&(k, _)| k == key);
+            match random.below(4) {
+                0 => {
+                    assert_eq!(cache.get(&key), found.map(|i| &model[i].1));
+                    if let Some(i) = found {
+                        let entry = model.remove(i);
+                        model.insert(0, entry);
+                    }
+                }
+                1 => {
+                    assert_eq!(cache.peek(&key), found.map(|i| &model[i].1));
+                }
+                2 => {
+                    assert_eq!(cache.remove(&key), found.map(|i| model.remove(i).1));
+                }
+                _ => {
+                    let old = found.map(|i| model.remove(i).1);
+                    assert_eq!(cache.put(key, value), old);
+                    model.insert(0, (key, value));
+                    model.truncate(CAPACITY);
+                }
+            }
+            assert!(cache
+                .iter()
+                .map(|(&k, &v)| (k, v))
+                .eq(model.iter().copied()));
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use doubly_linked_list::*;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashSet, LinkedList as StdList};
+use std::hash::{Hash, Hasher};
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn default_is_empty() {
+    let list: LinkedList<i32> = Default::default();
+    assert!(list.is_empty());
+}
+
+#[test]
+fn clone_is_independent() {
+    let list: LinkedList<_> = (0..5).map(|n| n.to_string()).collect();
+    let mut copy = list.clone();
+    copy.push_back("5".to_string());
+    *copy.front_mut().unwrap() = "zero".to_string();
+    assert_eq!(list.len(), 5);
+    assert_eq!(list.front().map(String::as_str), Some("0"));
+    assert_eq!(copy.len(), 6);
+}
+
+#[test]
+fn debug_looks_like_a_std_list() {
+    let list: LinkedList<_> = (0..3).collect();
+    let std_list: StdList<_> = (0..3).collect();
+    assert_eq!(format!("{:?}", list), "[0, 1, 2]");
+    assert_eq!(format!("{:?}", list), format!("{:?}", std_list));
+    assert_eq!(format!("{:?}", LinkedList::<i32>::new()), "[]");
+}
+
+#[test]
+fn equality() {
+    let a: LinkedList<_> = (0..3).collect();
+    let b: LinkedList<_> = (0..3).collect();
+    let c: LinkedList<_> = (0..4).collect();
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+    assert_ne!(c, a);
+    assert_eq!(LinkedList::<i32>::new(), LinkedList::new());
+}
+
+#[test]
+fn ordering_is_lexicographic() {
+    let list = |values: &[i32]| values.iter().copied().collect::<LinkedList<_>>();
+    assert!(list(&[1, 2]) < list(&[1, 3]));
+    assert!(list(&[1, 2]) < list(&[1, 2, 0]));
+    assert!(list(&[]) < list(&[0]));
+    assert_eq!(list(&[2]).cmp(&list(&[1, 9])), Ordering::Greater);
+    assert_eq!(list(&[1, 2]).cmp(&list(&[1, 2])), Ordering::Equal);
+
+    let floats: LinkedList<_> = [1.0, f64::NAN].iter().copied().collect();
+    assert_eq!(floats.partial_cmp(&floats), None);
+}
+
+#[test]
+fn hash_follows_equality() {
+    let a: LinkedList<_> = (0..3).collect();
+    let b: LinkedList<_> = (0..3).collect();
+    assert_eq!(hash_of(&a), hash_of(&b));
+
+    // same elements, split differently
+    let mut x = LinkedList::new();
+    x.push_back((0..1).collect::<LinkedList<_>>());
+    x.push_back((1..3).collect());
+    let mut y = LinkedList::new();
+    y.push_back((0..2).collect::<LinkedList<_>>());
+    y.push_back((2..3).collect());
+    assert_ne!(hash_of(&x), hash_of(&y));
+
+    let set: HashSet<_> = vec![a, b].into_iter().collect();
+    assert_eq!(set.len(), 1);
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn serde_roundtrip() {
+    let list: LinkedList<_> = (0..3).map(|n| n.to_string()).collect();
+    let json = serde_json::to_string(&list).unwrap();
+    assert_eq!(json, r#"["0","1","2"]"#);
+    let back: LinkedList<String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, list);
+
+    assert!(serde_json::from_str::<LinkedList<i32>>("{}").is_err());
+}
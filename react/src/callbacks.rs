@@ -0,0 +1,96 @@
+//! Callbacks of a cell (or a reactor) kept in the order they were added,
+//! each under a key never handed out again by the same registry.
+//!
+//! A removed callback leaves no hole behind, and neither does one
+//! which `Subscription` is gone, it's dropped the next time it's due
+//! or another callback is added, whichever comes first.
+
+use std::sync::{Arc, Weak};
+
+struct Entry<F> {
+    key: usize,
+    f: F,
+    // None if it stays until removed explicitly
+    guard: Option<Weak<()>>,
+}
+
+impl<F> Entry<F> {
+    fn alive(&self) -> bool {
+        self.guard.as_ref().is_none_or(|g| g.strong_count() > 0)
+    }
+}
+
+pub(crate) struct Callbacks<F> {
+    entries: Vec<Entry<F>>,
+    next_key: usize,
+}
+
+impl<F> Callbacks<F> {
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            next_key: 0,
+        }
+    }
+
+    // Returns the key to remove the callback with.
+    pub(crate) fn add(&mut self, f: F, guard: Option<Weak<()>>) -> usize {
+        // so that subscribing and dropping over and over does not pile them up
+        self.entries.retain(Entry::alive);
+        let key = self.next_key;
+        self.next_key += 1;
+        self.entries.push(Entry { key, f, guard });
+        key
+    }
+
+    pub(crate) fn remove(&mut self, key: usize) -> Option<F> {
+        // the keys only grow, so the entries are sorted by them
+        let i = self.entries.binary_search_by_key(&key, |e| e.key).ok()?;
+        Some(self.entries.remove(i).f)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        !self.entries.iter().any(Entry::alive)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &F> {
+        self.entries.iter().filter(|e| e.alive()).map(|e| &e.f)
+    }
+
+    // Keeps only the callbacks `f` returns true for.
+    pub(crate) fn retain(&mut self, mut f: impl FnMut(&F) -> bool) {
+        self.entries.retain(|e| e.alive() && f(&e.f));
+    }
+
+    // Calls `call` with each callback, dropping the ones no longer wanted.
+    pub(crate) fn for_each(&mut self, mut call: impl FnMut(&mut F)) {
+        self.entries.retain_mut(|e| {
+            if !e.alive() {
+                return false;
+            }
+            call(&mut e.f);
+            true
+        });
+    }
+}
+
+/// `Subscription` keeps a callback registered for as long as it's around,
+/// see `Reactor::add_scoped_callback`.
+///
+/// The callback is not called anymore once the subscription is dropped,
+/// and is dropped itself the next time it would have been called
+/// or another callback is added to the same cell.
+#[must_use = "the callback is removed as soon as the subscription is dropped"]
+pub struct Subscription {
+    // only its weak side matters, see `Entry::alive`
+    _guard: Arc<()>,
+}
+
+impl Subscription {
+    // The weak side goes to `Callbacks::add`.
+    pub(crate) fn new() -> (Self, Weak<()>) {
+        let guard = Arc::new(());
+        let weak = Arc::downgrade(&guard);
+        (Self { _guard: guard }, weak)
+    }
+}
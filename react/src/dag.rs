@@ -0,0 +1,282 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct NodeID {
+    index: usize,
+    generation: usize,
+}
+
+impl NodeID {
+    // unique among the live nodes, but not over time
+    pub(crate) fn index(&self) -> usize {
+        self.index
+    }
+}
+
+struct Node<T> {
+    value: T,
+    // longest path from a root, parents are always strictly lower
+    height: usize,
+    parents: Vec<NodeID>,
+    peers: Vec<NodeID>,
+}
+
+// A freed slot keeps its generation bumped, so that stale ids
+// pointing to it do not resolve to whatever lives there next.
+struct Slot<T> {
+    generation: usize,
+    node: Option<Node<T>>,
+}
+
+pub(crate) struct SimpleDAG<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+}
+
+impl<T> SimpleDAG<T> {
+    pub(crate) fn new() -> SimpleDAG<T> {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    // A DAG without nodes but with slots of the given generations,
+    // so that the nodes of another DAG can be put back with `insert`.
+    pub(crate) fn with_generations(generations: Vec<usize>) -> SimpleDAG<T> {
+        let slots: Vec<_> = generations
+            .into_iter()
+            .map(|generation| Slot {
+                generation,
+                node: None,
+            })
+            .collect();
+        let free = (0..slots.len()).rev().collect();
+        Self { slots, free }
+    }
+
+    pub(crate) fn generations(&self) -> Vec<usize> {
+        self.slots.iter().map(|slot| slot.generation).collect()
+    }
+
+    // Adds a node depending on the given (existing) parents.
+    // The DAG stays acyclic by construction: a brand new node
+    // can not be an ancestor of anything yet.
+    pub(crate) fn add(&mut self, value: T, parents: &[NodeID]) -> NodeID {
+        let index = loop {
+            match self.free.pop() {
+                // taken by `insert` in the meantime
+                Some(index) if self.slots[index].node.is_some() => continue,
+                Some(index) => break index,
+                None => {
+                    self.slots.push(Slot {
+                        generation: 0,
+                        node: None,
+                    });
+                    break self.slots.len() - 1;
+                }
+            }
+        };
+
+        let id = NodeID {
+            index,
+            generation: self.slots[index].generation,
+        };
+        self.place(id, value, parents);
+        id
+    }
+
+    // Same as `add`, but puts the node under the given id,
+    // which has to point to a free slot.
+    //
+    // Returns false if it does not.
+    pub(crate) fn insert(&mut self, id: NodeID, value: T, parents: &[NodeID]) -> bool {
+        match self.slots.get(id.index) {
+            Some(slot) if slot.generation == id.generation && slot.node.is_none() => {
+                self.place(id, value, parents);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn place(&mut self, id: NodeID, value: T, parents: &[NodeID]) {
+        let height = parents
+            .iter()
+            .map(|&p| self.node(p).expect("`parent` does not exist").height + 1)
+            .max()
+            .unwrap_or(0);
+
+        self.slots[id.index].node = Some(Node {
+            value,
+            height,
+            parents: parents.into(),
+            peers: Vec::new(),
+        });
+
+        for &p in parents {
+            self.node_mut(p).unwrap().peers.push(id);
+        }
+    }
+
+    // Removes a node that has no children left and returns its value.
+    pub(crate) fn remove(&mut self, id: NodeID) -> Option<T> {
+        let node = self.node(id)?;
+        assert!(node.peers.is_empty(), "can not remove a node with children");
+
+        let slot = &mut self.slots[id.index];
+        let node = slot.node.take()?;
+        slot.generation += 1;
+        self.free.push(id.index);
+
+        for p in node.parents {
+            if let Some(parent) = self.node_mut(p) {
+                parent.peers.retain(|&c| c != id);
+            }
+        }
+
+        Some(node.value)
+    }
+
+    // Replaces the parents of a node, keeping its children.
+    //
+    // Returns an Err with the first of the `parents` which is the node itself
+    // or one of its descendants, as the new edge would close a cycle then.
+    // Nothing changes in that case.
+    pub(crate) fn set_parents(&mut self, id: NodeID, parents: &[NodeID]) -> Result<(), NodeID> {
+        let descendants = self.descendants(id);
+        let below: HashSet<_> = descendants.iter().chain(Some(&id)).collect();
+        if let Some(&p) = parents.iter().find(|p| below.contains(p)) {
+            return Err(p);
+        }
+
+        let node = self.node_mut(id).expect("`id` does not exist");
+        let old_parents = std::mem::replace(&mut node.parents, parents.into());
+        for p in old_parents {
+            if let Some(parent) = self.node_mut(p) {
+                parent.peers.retain(|&c| c != id);
+            }
+        }
+        for &p in parents {
+            self.node_mut(p)
+                .expect("`parent` does not exist")
+                .peers
+                .push(id);
+        }
+
+        // the old heights still order the descendants among themselves,
+        // as none of the edges between them has changed
+        for n in Some(id).into_iter().chain(descendants) {
+            let node = self.node(n).unwrap();
+            let height = node
+                .parents
+                .iter()
+                .map(|&p| self.height(p) + 1)
+                .max()
+                .unwrap_or(0);
+            self.node_mut(n).unwrap().height = height;
+        }
+
+        Ok(())
+    }
+
+    fn node(&self, id: NodeID) -> Option<&Node<T>> {
+        self.slots
+            .get(id.index)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.node.as_ref())
+    }
+
+    fn node_mut(&mut self, id: NodeID) -> Option<&mut Node<T>> {
+        self.slots
+            .get_mut(id.index)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.node.as_mut())
+    }
+
+    pub(crate) fn get(&self, id: NodeID) -> Option<&T> {
+        self.node(id).map(|node| &node.value)
+    }
+
+    pub(crate) fn get_mut(&mut self, id: NodeID) -> Option<&mut T> {
+        self.node_mut(id).map(|node| &mut node.value)
+    }
+
+    pub(crate) fn height(&self, id: NodeID) -> usize {
+        self.node(id).map_or(0, |node| node.height)
+    }
+
+    pub(crate) fn children(&self, id: NodeID) -> &[NodeID] {
+        self.node(id).map_or(&[], |node| &node.peers)
+    }
+
+    // All the nodes, parents always go before their children.
+    pub(crate) fn topological_order(&self) -> Vec<NodeID> {
+        let mut ids: Vec<_> = self
+            .slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.node.is_some())
+            .map(|(index, slot)| NodeID {
+                index,
+                generation: slot.generation,
+            })
+            .collect();
+        ids.sort_by_key(|&id| self.height(id));
+        ids
+    }
+
+    // All the nodes reachable from `id` (excluding itself),
+    // each one listed once and ordered by height.
+    pub(crate) fn descendants(&self, id: NodeID) -> Vec<NodeID> {
+        let mut seen = HashSet::new();
+        let mut stack = self.children(id).to_vec();
+        while let Some(id) = stack.pop() {
+            if seen.insert(id) {
+                stack.extend_from_slice(self.children(id));
+            }
+        }
+        let mut descendants: Vec<_> = seen.into_iter().collect();
+        descendants.sort_by_key(|&id| self.height(id));
+        descendants
+    }
+}
+
+// Hands out the nodes to recompute in topological order, lowest height first.
+// A node is scheduled only when one of its parents has changed
+// and handed out at most once, however many of its parents did change.
+//
+// A node scheduled twice sits twice in the queue, but both entries are
+// equal and so come out one right after the other. Its parents all being
+// lower, none of them can schedule it again once it has come out.
+pub(crate) struct Scheduler {
+    queue: BinaryHeap<Reverse<(usize, NodeID)>>,
+}
+
+impl Scheduler {
+    pub(crate) fn new() -> Self {
+        Self {
+            queue: BinaryHeap::new(),
+        }
+    }
+
+    pub(crate) fn schedule<T>(&mut self, dag: &SimpleDAG<T>, id: NodeID) {
+        self.queue.push(Reverse((dag.height(id), id)));
+    }
+
+    pub(crate) fn schedule_children<T>(&mut self, dag: &SimpleDAG<T>, id: NodeID) {
+        for &c in dag.children(id) {
+            self.schedule(dag, c);
+        }
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<NodeID> {
+        let Reverse(next) = self.queue.pop()?;
+        while self.queue.peek() == Some(&Reverse(next)) {
+            self.queue.pop();
+        }
+        Some(next.1)
+    }
+}
@@ -0,0 +1,66 @@
+//! Input changes kept around to be undone and redone,
+//! see `Reactor::record_history`.
+
+use crate::dag::NodeID;
+use std::collections::VecDeque;
+
+// The value of an input cell before and after a change.
+pub(crate) struct Change<T> {
+    pub(crate) id: NodeID,
+    pub(crate) before: T,
+    pub(crate) after: T,
+}
+
+// Each entry is all the inputs changed by one `set_value` or batch.
+pub(crate) struct History<T> {
+    done: VecDeque<Vec<Change<T>>>,
+    undone: Vec<Vec<Change<T>>>,
+    limit: usize,
+}
+
+impl<T: Clone> History<T> {
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            done: VecDeque::new(),
+            undone: Vec::new(),
+            limit,
+        }
+    }
+
+    pub(crate) fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.trim();
+    }
+
+    // A new change can not be followed by the ones undone before it.
+    pub(crate) fn record(&mut self, changes: Vec<Change<T>>) {
+        self.undone.clear();
+        self.done.push_back(changes);
+        self.trim();
+    }
+
+    // Returns the values to set to undo the last change.
+    pub(crate) fn undo(&mut self) -> Option<Vec<(NodeID, T)>> {
+        let changes = self.done.pop_back()?;
+        let values = changes.iter().map(|c| (c.id, c.before.clone())).collect();
+        self.undone.push(changes);
+        Some(values)
+    }
+
+    // Returns the values to set to redo the last undone change.
+    pub(crate) fn redo(&mut self) -> Option<Vec<(NodeID, T)>> {
+        let changes = self.undone.pop()?;
+        let values = changes.iter().map(|c| (c.id, c.after.clone())).collect();
+        self.done.push_back(changes);
+        Some(values)
+    }
+
+    // drops the oldest changes to undo and the furthest ones to redo
+    fn trim(&mut self) {
+        while self.done.len() > self.limit {
+            self.done.pop_front();
+        }
+        let over = self.undone.len().saturating_sub(self.limit);
+        self.undone.drain(..over);
+    }
+}
@@ -0,0 +1,126 @@
+//! Looking into the graph of a reactor, mostly for debugging:
+//! which cells depend on which, in what order they get computed,
+//! how often they have been, and all of it drawn as a Graphviz graph.
+
+use crate::dag::NodeID;
+use crate::{CellID, ComputeCellID, InputCellID, Reactor};
+use std::fmt::{Debug, Write};
+
+impl<'a, T: Clone + PartialEq, E: Clone> Reactor<'a, T, E> {
+    // Lists the cells the specified cell is computed from,
+    // in the order they were given to `create_compute`.
+    //
+    // Returns an empty list for an input cell, or None if the cell does not exist.
+    pub fn dependencies_of(&self, id: CellID) -> Option<Vec<CellID>> {
+        let cell = self.cell(id)?;
+        Some(
+            cell.compute
+                .as_ref()
+                .map_or_else(Vec::new, |compute| compute.dependencies.clone()),
+        )
+    }
+
+    // Lists the compute cells directly depending on the specified cell,
+    // in the order they were created.
+    //
+    // Returns None if the cell does not exist.
+    pub fn dependents_of(&self, id: CellID) -> Option<Vec<ComputeCellID>> {
+        self.cell(id)?;
+        let mut dependents: Vec<_> = self
+            .cells
+            .children(id.raw_id())
+            .iter()
+            .map(|&c| ComputeCellID(c))
+            .collect();
+        // the same dependent appears once per dependency on the cell
+        dependents.dedup();
+        Some(dependents)
+    }
+
+    // Returns the length of the longest chain of dependencies
+    // leading to the specified cell, zero for an input cell,
+    // or None if the cell does not exist.
+    pub fn depth_of(&self, id: CellID) -> Option<usize> {
+        self.cell(id)?;
+        Some(self.cells.height(id.raw_id()))
+    }
+
+    // Lists all the cells in an order they can be computed in:
+    // every cell comes after all of its dependencies.
+    //
+    // This is the order in which a change propagates, by depth.
+    pub fn topological_order(&self) -> Vec<CellID> {
+        self.cells
+            .topological_order()
+            .into_iter()
+            .map(|id| self.cell_id(id))
+            .collect()
+    }
+
+    // Returns how many times the specified compute cell has been recomputed
+    // since its creation, counting the times its value stayed the same
+    // but not the errors of its dependencies passed along without calling
+    // its compute function, or None if the cell does not exist.
+    pub fn recompute_count(&self, id: ComputeCellID) -> Option<usize> {
+        let compute = self.cell(CellID::Compute(id))?.compute.as_ref()?;
+        Some(compute.recomputed.get())
+    }
+
+    fn cell_id(&self, id: NodeID) -> CellID {
+        match self.cells.get(id).and_then(|cell| cell.compute.as_ref()) {
+            Some(_) => CellID::Compute(ComputeCellID(id)),
+            None => CellID::Input(InputCellID(id)),
+        }
+    }
+}
+
+impl<'a, T: Clone + PartialEq + Debug, E: Clone + Debug> Reactor<'a, T, E> {
+    // Renders the graph in the Graphviz DOT language, e.g. for `dot -Tsvg`.
+    //
+    // Input cells are boxes and compute cells are ellipses, each labeled
+    // with its current value, the name of its registered function if any,
+    // and how many times it has been recomputed. Edges go from a dependency
+    // to its dependent.
+    //
+    // Dirty lazy cells are labeled `dirty` instead of a value, rendering
+    // never computes anything.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph reactor {\n");
+        // writing to a String never fails
+        for id in self.topological_order() {
+            let cell = self.cell(id).unwrap();
+            let node = id.raw_id().index();
+            let value = match (
+                &cell.value,
+                cell.compute.as_ref().and_then(|c| c.lazy.as_ref()),
+            ) {
+                (_, Some(lazy)) if lazy.dirty => "dirty".to_string(),
+                (Ok(value), _) => format!("{:?}", value),
+                (Err(error), _) => format!("Err({:?})", error),
+            };
+            match &cell.compute {
+                None => {
+                    let label = format!("#{} = {}", node, value);
+                    writeln!(dot, "    cell{} [shape=box, label={:?}];", node, label).unwrap();
+                }
+                Some(compute) => {
+                    let name = compute.name.as_deref().unwrap_or("fn");
+                    let label = format!(
+                        "#{} = {}\n{}, recomputed {}",
+                        node,
+                        value,
+                        name,
+                        compute.recomputed.get()
+                    );
+                    writeln!(dot, "    cell{} [shape=ellipse, label={:?}];", node, label).unwrap();
+                    for dependency in &compute.dependencies {
+                        let from = dependency.raw_id().index();
+                        writeln!(dot, "    cell{} -> cell{};", from, node).unwrap();
+                    }
+                }
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -0,0 +1,759 @@
+//! A spreadsheet on top of `Reactor<f64>`, with cells defined by text
+//! instead of closures, for the people who'd rather not write Rust:
+//!
+//! ```
+//! let mut sheet = react::Sheet::new();
+//! sheet.import("1\t2\n=A1+B1\t=IF(A2 > 2, SUM(A1:B1, A2), 0)").unwrap();
+//! assert_eq!(sheet.value("B2"), Some(6.0));
+//!
+//! sheet.define("A1", "-2").unwrap();
+//! assert_eq!(sheet.value("B2"), Some(0.0));
+//! ```
+//!
+//! A formula starts with `=` and is made of numbers, cell references (`A1`),
+//! the `+ - * /` arithmetic, comparisons (`= <> < > <= >=`, giving 1 or 0)
+//! and the `SUM`, `MIN`, `MAX` and `IF` functions. The functions taking
+//! any number of arguments take ranges (`A1:B3`) as well, of up to
+//! 10 000 cells, where the cells not defined are left out. Parentheses,
+//! function calls and signs nest up to 100 levels deep.
+
+use crate::{CellID, Reactor, SetComputeError};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+// the most cells a range can span
+const MAX_RANGE: usize = 10_000;
+// the most levels of parentheses, function calls and signs a formula can nest
+const MAX_DEPTH: usize = 100;
+
+/// `Sheet` is a reactor which cells are named the spreadsheet way, `A1`, `B2` and so on.
+pub struct Sheet<'a> {
+    reactor: Reactor<'a, f64>,
+    cells: HashMap<String, CellID>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FormulaError {
+    // the text is neither a number nor a formula that makes sense,
+    // `position` is the byte offset of the offending part
+    Parse { position: usize, message: String },
+    UnknownReference(String),
+    UnknownFunction(String),
+    WrongArgumentCount(String),
+    // the cell would end up depending on itself
+    Cycle(String),
+    // an input cell can not become a formula or the other way around
+    KindChange(String),
+    // not something like `A1`
+    InvalidName(String),
+}
+
+/// `ImportError` is a `FormulaError` in the cell named `cell`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportError {
+    pub cell: String,
+    pub error: FormulaError,
+}
+
+impl<'a> Default for Sheet<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Sheet<'a> {
+    pub fn new() -> Self {
+        Self {
+            reactor: Reactor::new(),
+            cells: HashMap::new(),
+        }
+    }
+
+    // Defines the cell `name` as either a number or a formula,
+    // e.g. `define("C1", "=A1 * 2")`. Names are case insensitive.
+    //
+    // Redefining a cell keeps its callbacks and updates all its dependents,
+    // but a number can not become a formula and a formula can not become a number.
+    //
+    // The cells of a range have to be defined before the formula for it to
+    // depend on them, the others are left out for good.
+    //
+    // Returns the id of the cell, to be used with `reactor`.
+    pub fn define(&mut self, name: &str, source: &str) -> Result<CellID, FormulaError> {
+        let name = name.to_ascii_uppercase();
+        if parse_name(&name).is_none() {
+            return Err(FormulaError::InvalidName(name));
+        }
+
+        match source.trim_start().strip_prefix('=') {
+            None => self.define_input(name, parse_number(source)?),
+            Some(formula) => {
+                let offset = source.len() - formula.len();
+                let formula = Formula::parse(formula).map_err(|e| e.shift(offset))?;
+                self.define_compute(name, formula)
+            }
+        }
+    }
+
+    fn define_input(&mut self, name: String, value: f64) -> Result<CellID, FormulaError> {
+        match self.cells.get(&name) {
+            Some(&id @ CellID::Input(input)) => {
+                self.reactor.set_value(input, value);
+                Ok(id)
+            }
+            Some(CellID::Compute(_)) => Err(FormulaError::KindChange(name)),
+            None => {
+                let id = CellID::Input(self.reactor.create_input(value));
+                self.cells.insert(name, id);
+                Ok(id)
+            }
+        }
+    }
+
+    fn define_compute(&mut self, name: String, formula: Formula) -> Result<CellID, FormulaError> {
+        // where the value of each reference is among the dependencies,
+        // None for the cells of ranges which are not defined
+        let mut slots = Vec::with_capacity(formula.references.len());
+        let mut dependencies = Vec::new();
+        for (r, &required) in formula.references.iter().zip(&formula.required) {
+            match self.cells.get(r) {
+                Some(&id) => {
+                    slots.push(Some(dependencies.len()));
+                    dependencies.push(id);
+                }
+                None if required => return Err(FormulaError::UnknownReference(r.clone())),
+                None => slots.push(None),
+            }
+        }
+        let mut expr = formula.expr;
+        expr.bind(&slots);
+        let f = move |values: &[f64]| expr.eval(values);
+
+        match self.cells.get(&name) {
+            Some(&id @ CellID::Compute(compute)) => {
+                match self.reactor.set_compute(compute, &dependencies, f) {
+                    Ok(()) => Ok(id),
+                    Err(SetComputeError::Cycle(_)) => Err(FormulaError::Cycle(name)),
+                    Err(e) => unreachable!("all the cells exist, got {:?}", e),
+                }
+            }
+            Some(CellID::Input(_)) => Err(FormulaError::KindChange(name)),
+            None => {
+                let id = self.reactor.create_compute(&dependencies, f);
+                let id = CellID::Compute(id.expect("all the cells exist"));
+                self.cells.insert(name, id);
+                Ok(id)
+            }
+        }
+    }
+
+    // Defines the cells of a grid given as text, one row per line
+    // and the columns separated by tabs, the way spreadsheets copy them.
+    // The first line is row 1 and the first column is column A,
+    // empty fields are skipped.
+    //
+    // The formulas may refer to the cells defined earlier as well as
+    // to the other cells of the grid, wherever they are.
+    //
+    // Stops at the first error, with the cells defined so far kept.
+    pub fn import(&mut self, grid: &str) -> Result<(), ImportError> {
+        let mut names = Vec::new();
+        let mut formulas = Vec::new();
+        for (row, line) in grid.lines().enumerate() {
+            for (column, source) in line.split('\t').enumerate() {
+                if source.trim().is_empty() {
+                    continue;
+                }
+                let name = format!("{}{}", column_name(column), row + 1);
+                let error = |error| ImportError {
+                    cell: name.clone(),
+                    error,
+                };
+                match source.trim_start().strip_prefix('=') {
+                    None => {
+                        let value = parse_number(source).map_err(error)?;
+                        self.define_input(name.clone(), value).map_err(error)?;
+                    }
+                    Some(formula) => {
+                        let offset = source.len() - formula.len();
+                        let formula =
+                            Formula::parse(formula).map_err(|e| error(e.shift(offset)))?;
+                        names.push(name);
+                        formulas.push(Some(formula));
+                    }
+                }
+            }
+        }
+
+        // The formulas go after the ones they refer to, in the order of
+        // Kahn's algorithm: `waiting` counts the formulas of the grid each
+        // one refers to which are not defined yet.
+        let index: HashMap<&str, usize> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.as_str(), i))
+            .collect();
+        let mut waiting = vec![0; formulas.len()];
+        let mut dependents = vec![Vec::new(); formulas.len()];
+        for (i, formula) in formulas.iter().enumerate() {
+            for r in &formula.as_ref().unwrap().references {
+                if let Some(&j) = index.get(r.as_str()) {
+                    waiting[i] += 1;
+                    dependents[j].push(i);
+                }
+            }
+        }
+
+        let mut ready = (0..formulas.len())
+            .filter(|&i| waiting[i] == 0)
+            .collect::<VecDeque<_>>();
+        while let Some(i) = ready.pop_front() {
+            let formula = formulas[i].take().unwrap();
+            self.define_compute(names[i].clone(), formula)
+                .map_err(|error| ImportError {
+                    cell: names[i].clone(),
+                    error,
+                })?;
+            for &j in &dependents[i] {
+                waiting[j] -= 1;
+                if waiting[j] == 0 {
+                    ready.push_back(j);
+                }
+            }
+        }
+
+        // The ones left refer to a cycle, found by following their references
+        // to each other from the first one until a cell comes back.
+        if let Some(mut i) = waiting.iter().position(|&w| w > 0) {
+            let mut seen = HashSet::new();
+            while seen.insert(i) {
+                let references = &formulas[i].as_ref().unwrap().references;
+                i = references
+                    .iter()
+                    .filter_map(|r| index.get(r.as_str()).copied())
+                    .find(|&j| waiting[j] > 0)
+                    .expect("a formula left refers to another one");
+            }
+            return Err(ImportError {
+                cell: names[i].clone(),
+                error: FormulaError::Cycle(names[i].clone()),
+            });
+        }
+        Ok(())
+    }
+
+    // Retrieves the current value of the cell, or None if it's not defined.
+    pub fn value(&self, name: &str) -> Option<f64> {
+        self.reactor.value(self.id(name)?)
+    }
+
+    // Returns the id of the cell, or None if it's not defined.
+    pub fn id(&self, name: &str) -> Option<CellID> {
+        self.cells.get(&name.to_ascii_uppercase()).copied()
+    }
+
+    // The reactor behind the sheet, for adding callbacks and the like.
+    pub fn reactor(&mut self) -> &mut Reactor<'a, f64> {
+        &mut self.reactor
+    }
+}
+
+fn parse_number(source: &str) -> Result<f64, FormulaError> {
+    source.trim().parse().map_err(|_| FormulaError::Parse {
+        position: 0,
+        message: "expected a number or a formula starting with `=`".into(),
+    })
+}
+
+// Splits `AB12` into the column `27` (zero based) and the row `12`.
+fn parse_name(name: &str) -> Option<(usize, usize)> {
+    let digits = name.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = name.split_at(digits);
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+    if digits.starts_with('0') || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let column = letters.bytes().try_fold(0usize, |column, c| {
+        column.checked_mul(26)?.checked_add((c - b'A') as usize + 1)
+    })?;
+    Some((column - 1, digits.parse().ok()?))
+}
+
+// The reverse of `parse_name`: 0 is `A`, 26 is `AA`.
+fn column_name(mut column: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push(b'A' + (column % 26) as u8);
+        if column < 26 {
+            break;
+        }
+        column = column / 26 - 1;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+impl Op {
+    fn apply(self, a: f64, b: f64) -> f64 {
+        let truth = |b: bool| if b { 1.0 } else { 0.0 };
+        match self {
+            Op::Add => a + b,
+            Op::Sub => a - b,
+            Op::Mul => a * b,
+            Op::Div => a / b,
+            Op::Eq => truth(a == b),
+            Op::Ne => truth(a != b),
+            Op::Lt => truth(a < b),
+            Op::Gt => truth(a > b),
+            Op::Le => truth(a <= b),
+            Op::Ge => truth(a >= b),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Function {
+    Sum,
+    Min,
+    Max,
+    If,
+}
+
+#[derive(Debug)]
+enum Expr {
+    Number(f64),
+    // an index into `Formula::references`
+    Ref(usize),
+    // the cells of a range, only ever an argument of a function
+    Range(Vec<usize>),
+    Neg(Box<Expr>),
+    // `a op b op c ...` from left to right, flat so that long sums
+    // do not nest any deeper than their terms
+    Chain(Box<Expr>, Vec<(Op, Expr)>),
+    Call(Function, Vec<Expr>),
+}
+
+impl Expr {
+    // Turns the indexes into `Formula::references` into indexes into
+    // the dependencies, given by `slots`, leaving out the cells of ranges
+    // which have none.
+    fn bind(&mut self, slots: &[Option<usize>]) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Ref(i) => *i = slots[*i].expect("a cell outside of a range is defined"),
+            Expr::Range(cells) => *cells = cells.iter().filter_map(|&i| slots[i]).collect(),
+            Expr::Neg(e) => e.bind(slots),
+            Expr::Chain(a, rest) => {
+                a.bind(slots);
+                rest.iter_mut().for_each(|(_, b)| b.bind(slots));
+            }
+            Expr::Call(_, args) => args.iter_mut().for_each(|a| a.bind(slots)),
+        }
+    }
+
+    // `values` go in the order of the dependencies
+    fn eval(&self, values: &[f64]) -> f64 {
+        match self {
+            Expr::Number(n) => *n,
+            Expr::Ref(i) => values[*i],
+            Expr::Range(_) => unreachable!("a range outside of a function"),
+            Expr::Neg(e) => -e.eval(values),
+            Expr::Chain(a, rest) => rest
+                .iter()
+                .fold(a.eval(values), |a, (op, b)| op.apply(a, b.eval(values))),
+            Expr::Call(Function::If, args) => {
+                if args[0].eval(values) != 0.0 {
+                    args[1].eval(values)
+                } else {
+                    args[2].eval(values)
+                }
+            }
+            Expr::Call(function, args) => {
+                let values = args.iter().flat_map(|a| {
+                    let (value, range) = match a {
+                        Expr::Range(cells) => (None, &cells[..]),
+                        a => (Some(a.eval(values)), &[][..]),
+                    };
+                    value.into_iter().chain(range.iter().map(|&i| values[i]))
+                });
+                // 0 for nothing at all, like spreadsheets do
+                match function {
+                    Function::Sum => values.sum(),
+                    Function::Min => values.reduce(f64::min).unwrap_or(0.0),
+                    Function::Max => values.reduce(f64::max).unwrap_or(0.0),
+                    Function::If => unreachable!(),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    // a cell or a function name, uppercased
+    Name(String),
+    Op(Op),
+    Open,
+    Close,
+    Comma,
+    Colon,
+}
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, FormulaError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(position, c)) = chars.peek() {
+        let token = match c {
+            _ if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '0'..='9' | '.' => {
+                let mut end = position;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_ascii_digit() || c == '.') {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                let number = source[position..end]
+                    .parse()
+                    .map_err(|_| FormulaError::Parse {
+                        position,
+                        message: "invalid number".into(),
+                    })?;
+                tokens.push((position, Token::Number(number)));
+                continue;
+            }
+            _ if c.is_ascii_alphabetic() => {
+                let mut end = position;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !c.is_ascii_alphanumeric() {
+                        break;
+                    }
+                    end = i + 1;
+                    chars.next();
+                }
+                let name = source[position..end].to_ascii_uppercase();
+                tokens.push((position, Token::Name(name)));
+                continue;
+            }
+            '+' => Token::Op(Op::Add),
+            '-' => Token::Op(Op::Sub),
+            '*' => Token::Op(Op::Mul),
+            '/' => Token::Op(Op::Div),
+            '=' => Token::Op(Op::Eq),
+            '<' | '>' => {
+                chars.next();
+                let op = match (c, chars.peek().map(|&(_, c)| c)) {
+                    ('<', Some('=')) => Op::Le,
+                    ('<', Some('>')) => Op::Ne,
+                    ('>', Some('=')) => Op::Ge,
+                    ('<', _) => Op::Lt,
+                    _ => Op::Gt,
+                };
+                if matches!(op, Op::Le | Op::Ne | Op::Ge) {
+                    chars.next();
+                }
+                tokens.push((position, Token::Op(op)));
+                continue;
+            }
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            _ => {
+                return Err(FormulaError::Parse {
+                    position,
+                    message: format!("unexpected `{}`", c),
+                })
+            }
+        };
+        chars.next();
+        tokens.push((position, token));
+    }
+    Ok(tokens)
+}
+
+// A parsed formula, not yet bound to any cells.
+struct Formula {
+    expr: Expr,
+    // the names of the cells referred to, each one once
+    references: Vec<String>,
+    // whether each of them is referred to outside of a range,
+    // so that it has to be defined
+    required: Vec<bool>,
+}
+
+impl FormulaError {
+    // positions are relative to the formula, without the `=`
+    fn shift(self, offset: usize) -> Self {
+        match self {
+            FormulaError::Parse { position, message } => FormulaError::Parse {
+                position: position + offset,
+                message,
+            },
+            e => e,
+        }
+    }
+}
+
+// A recursive descent parser, one method per precedence level:
+//
+// comparison := sum (("=" | "<>" | "<" | ">" | "<=" | ">=") sum)?
+// sum        := product (("+" | "-") product)*
+// product    := unary (("*" | "/") unary)*
+// unary      := "-" unary | primary
+// primary    := number | cell | name "(" arguments? ")" | "(" comparison ")"
+// arguments  := (cell ":" cell | comparison) ("," arguments)?
+//
+// The recursion goes no deeper than `MAX_DEPTH` levels of `unary` and
+// `primary`, and neither does the `Expr` it gives.
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    end: usize,
+    depth: usize,
+    references: Vec<String>,
+    required: Vec<bool>,
+    // the index of each reference
+    indexes: HashMap<String, usize>,
+}
+
+impl Formula {
+    fn parse(source: &str) -> Result<Self, FormulaError> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            next: 0,
+            end: source.len(),
+            depth: 0,
+            references: Vec::new(),
+            required: Vec::new(),
+            indexes: HashMap::new(),
+        };
+        let expr = parser.comparison()?;
+        if parser.peek().is_some() {
+            return Err(parser.error("expected an operator"));
+        }
+        Ok(Formula {
+            expr,
+            references: parser.references,
+            required: parser.required,
+        })
+    }
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, t)| t)
+    }
+
+    fn take(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.next += 1;
+        token
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<(), FormulaError> {
+        if self.peek() == Some(&token) {
+            self.next += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {}", what)))
+        }
+    }
+
+    fn error(&self, message: &str) -> FormulaError {
+        let position = self.tokens.get(self.next).map_or(self.end, |&(p, _)| p);
+        FormulaError::Parse {
+            position,
+            message: message.into(),
+        }
+    }
+
+    fn reference(&mut self, name: String, required: bool) -> usize {
+        let index = match self.indexes.get(&name) {
+            Some(&index) => index,
+            None => {
+                self.indexes.insert(name.clone(), self.references.len());
+                self.references.push(name);
+                self.required.push(false);
+                self.references.len() - 1
+            }
+        };
+        self.required[index] |= required;
+        index
+    }
+
+    // Parses what comes next one level deeper.
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, FormulaError>,
+    ) -> Result<T, FormulaError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(&format!("nested more than {} levels deep", MAX_DEPTH)));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn chain(a: Expr, rest: Vec<(Op, Expr)>) -> Expr {
+        if rest.is_empty() {
+            a
+        } else {
+            Expr::Chain(Box::new(a), rest)
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, FormulaError> {
+        let a = self.sum()?;
+        let mut rest = Vec::new();
+        if let Some(&Token::Op(op @ (Op::Eq | Op::Ne | Op::Lt | Op::Gt | Op::Le | Op::Ge))) =
+            self.peek()
+        {
+            self.next += 1;
+            rest.push((op, self.sum()?));
+        }
+        Ok(Self::chain(a, rest))
+    }
+
+    fn sum(&mut self) -> Result<Expr, FormulaError> {
+        let a = self.product()?;
+        let mut rest = Vec::new();
+        while let Some(&Token::Op(op @ (Op::Add | Op::Sub))) = self.peek() {
+            self.next += 1;
+            rest.push((op, self.product()?));
+        }
+        Ok(Self::chain(a, rest))
+    }
+
+    fn product(&mut self) -> Result<Expr, FormulaError> {
+        let a = self.unary()?;
+        let mut rest = Vec::new();
+        while let Some(&Token::Op(op @ (Op::Mul | Op::Div))) = self.peek() {
+            self.next += 1;
+            rest.push((op, self.unary()?));
+        }
+        Ok(Self::chain(a, rest))
+    }
+
+    fn unary(&mut self) -> Result<Expr, FormulaError> {
+        if self.peek() == Some(&Token::Op(Op::Sub)) {
+            self.next += 1;
+            return Ok(Expr::Neg(Box::new(self.nested(Self::unary)?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, FormulaError> {
+        let error = self.error("expected a number, a cell, a function or `(`");
+        match self.take() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Open) => {
+                let expr = self.nested(Self::comparison)?;
+                self.expect(Token::Close, "`)`")?;
+                Ok(expr)
+            }
+            Some(Token::Name(name)) if self.peek() == Some(&Token::Open) => {
+                self.next += 1;
+                self.nested(|parser| parser.call(name))
+            }
+            Some(Token::Name(name)) if parse_name(&name).is_some() => {
+                Ok(Expr::Ref(self.reference(name, true)))
+            }
+            _ => Err(error),
+        }
+    }
+
+    fn call(&mut self, name: String) -> Result<Expr, FormulaError> {
+        let function = match name.as_str() {
+            "SUM" => Function::Sum,
+            "MIN" => Function::Min,
+            "MAX" => Function::Max,
+            "IF" => Function::If,
+            _ => return Err(FormulaError::UnknownFunction(name)),
+        };
+
+        let mut args = Vec::new();
+        if self.peek() != Some(&Token::Close) {
+            loop {
+                self.argument(&mut args, function != Function::If)?;
+                if self.peek() != Some(&Token::Comma) {
+                    break;
+                }
+                self.next += 1;
+            }
+        }
+        self.expect(Token::Close, "`,` or `)`")?;
+
+        let count_ok = match function {
+            Function::If => args.len() == 3,
+            _ => !args.is_empty(),
+        };
+        if !count_ok {
+            return Err(FormulaError::WrongArgumentCount(name));
+        }
+        Ok(Expr::Call(function, args))
+    }
+
+    // A range is a single argument standing for all of its cells.
+    fn argument(&mut self, args: &mut Vec<Expr>, ranges: bool) -> Result<(), FormulaError> {
+        let range = match (self.tokens.get(self.next), self.tokens.get(self.next + 1)) {
+            (Some((_, Token::Name(from))), Some((_, Token::Colon))) if ranges => from.clone(),
+            _ => {
+                args.push(self.comparison()?);
+                return Ok(());
+            }
+        };
+        let from = parse_name(&range);
+        let start = self.next;
+        self.next += 2;
+        let to = match self.take() {
+            Some(Token::Name(to)) => parse_name(&to),
+            _ => None,
+        };
+        let ((c1, r1), (c2, r2)) = match (from, to) {
+            (Some(from), Some(to)) => (from, to),
+            _ => {
+                self.next -= 1;
+                return Err(self.error("expected a range of cells like `A1:B2`"));
+            }
+        };
+
+        let (columns, rows) = (c1.min(c2)..=c1.max(c2), r1.min(r2)..=r1.max(r2));
+        let size = (columns.end() - columns.start() + 1).checked_mul(rows.end() - rows.start() + 1);
+        if size.is_none_or(|size| size > MAX_RANGE) {
+            self.next = start;
+            return Err(self.error(&format!("a range of more than {} cells", MAX_RANGE)));
+        }
+
+        let mut cells = Vec::new();
+        for column in columns {
+            for row in rows.clone() {
+                let name = format!("{}{}", column_name(column), row);
+                cells.push(self.reference(name, false));
+            }
+        }
+        args.push(Expr::Range(cells));
+        Ok(())
+    }
+}
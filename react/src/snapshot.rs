@@ -0,0 +1,235 @@
+//! Saving the shape of a reactor together with the values of its inputs,
+//! to build the same reactor again later, say on the next run of the app.
+//!
+//! Functions can not be saved, so the compute cells refer to theirs
+//! by the name they are registered under in a `Registry`. With the `serde`
+//! feature on, a `Snapshot` can be written to any serde format.
+
+use crate::callbacks::Callbacks;
+use crate::dag::SimpleDAG;
+use crate::{
+    cloned, evaluate, Cell, CellID, Compute, ComputeCellID, ComputeFn, InputCellID, Reactor,
+};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::rc::Rc;
+
+type SharedFn<'a, T, E> = Rc<dyn Fn(&[&T]) -> Result<T, E> + 'a>;
+
+/// `Registry` maps names to compute functions,
+/// see `Reactor::create_registered` and `Reactor::restore`.
+pub struct Registry<'a, T, E = Infallible> {
+    functions: HashMap<String, SharedFn<'a, T, E>>,
+}
+
+/// `Snapshot` is the state of a reactor as returned by `Reactor::snapshot`.
+///
+/// Callbacks, subscriptions and group callbacks are not part of it.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot<T> {
+    // the generation of every slot, so that the ids of the removed cells
+    // stay invalid and the restored cells keep theirs
+    generations: Vec<usize>,
+    // parents always go before their children
+    pub cells: Vec<CellSnapshot<T>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CellSnapshot<T> {
+    Input {
+        id: InputCellID,
+        value: T,
+    },
+    Compute {
+        id: ComputeCellID,
+        dependencies: Vec<CellID>,
+        // the name in the registry
+        function: String,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SnapshotError {
+    // the compute cell was not created with `create_registered`
+    UnnamedCompute(ComputeCellID),
+    // no function is registered under the name
+    UnknownFunction(String),
+    MissingDependency(CellID),
+    // the id does not fit the snapshot or is used twice
+    InvalidCell(CellID),
+}
+
+impl<'a, T: 'a> Default for Registry<'a, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T: 'a> Registry<'a, T> {
+    pub fn new() -> Self {
+        Self::new_fallible()
+    }
+}
+
+impl<'a, T: 'a, E: 'a> Registry<'a, T, E> {
+    // Creates a registry for the functions of the reactors made with `Reactor::new_fallible`.
+    pub fn new_fallible() -> Self {
+        Self {
+            functions: HashMap::new(),
+        }
+    }
+
+    // Registers a compute function under `name`, replacing the previous one if any.
+    // See `Reactor::create_compute` for what the function gets.
+    pub fn register<F: Fn(&[T]) -> T + 'a>(&mut self, name: &str, f: F)
+    where
+        T: Clone,
+    {
+        self.register_try(name, move |values| Ok(f(values)));
+    }
+
+    // Same as `register`, but for `Reactor::create_compute_ref` like functions.
+    pub fn register_ref<F: Fn(&[&T]) -> T + 'a>(&mut self, name: &str, f: F) {
+        self.register_try_ref(name, move |values| Ok(f(values)));
+    }
+
+    // Same as `register`, but for `Reactor::create_try_compute` like functions.
+    pub fn register_try<F: Fn(&[T]) -> Result<T, E> + 'a>(&mut self, name: &str, f: F)
+    where
+        T: Clone,
+    {
+        self.register_try_ref(name, move |values| cloned(&f, values));
+    }
+
+    // Same as `register_try`, but for `Reactor::create_try_compute_ref` like functions.
+    pub fn register_try_ref<F: Fn(&[&T]) -> Result<T, E> + 'a>(&mut self, name: &str, f: F) {
+        self.functions.insert(name.into(), Rc::new(f));
+    }
+
+    fn get(&self, name: &str) -> Result<ComputeFn<'a, T, E>, SnapshotError> {
+        let f = self
+            .functions
+            .get(name)
+            .ok_or_else(|| SnapshotError::UnknownFunction(name.into()))?;
+        let f = Rc::clone(f);
+        Ok(Box::new(move |values| f(values)))
+    }
+}
+
+impl<'a, T: Clone + PartialEq, E: Clone> Reactor<'a, T, E> {
+    // Same as `create_compute`, but the compute function is the one
+    // registered under `name`, which lets `snapshot` save the cell.
+    pub fn create_registered(
+        &mut self,
+        registry: &Registry<'a, T, E>,
+        name: &str,
+        dependencies: &[CellID],
+    ) -> Result<ComputeCellID, SnapshotError>
+    where
+        T: 'a,
+        E: 'a,
+    {
+        let f = registry.get(name)?;
+        let compute = Compute {
+            name: Some(name.into()),
+            ..Compute::new(dependencies, f)
+        };
+        self.add_compute(compute)
+            .map_err(SnapshotError::MissingDependency)
+    }
+
+    // Saves all the cells, which works only if every compute cell
+    // was created with `create_registered`.
+    pub fn snapshot(&self) -> Result<Snapshot<T>, SnapshotError> {
+        let cells = self
+            .cells
+            .topological_order()
+            .into_iter()
+            .map(|id| {
+                let cell = self.cells.get(id).unwrap();
+                match &cell.compute {
+                    None => Ok(CellSnapshot::Input {
+                        id: InputCellID(id),
+                        value: match &cell.value {
+                            Ok(value) => value.clone(),
+                            Err(_) => unreachable!("input cells never fail"),
+                        },
+                    }),
+                    Some(compute) => Ok(CellSnapshot::Compute {
+                        id: ComputeCellID(id),
+                        dependencies: compute.dependencies.clone(),
+                        function: compute
+                            .name
+                            .clone()
+                            .ok_or(SnapshotError::UnnamedCompute(ComputeCellID(id)))?,
+                    }),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Snapshot {
+            generations: self.cells.generations(),
+            cells,
+        })
+    }
+
+    // Builds the reactor a snapshot was taken of, looking up
+    // the compute functions in the `registry` by their names.
+    //
+    // All the cells keep their ids, and all the compute cells
+    // get computed anew from the restored inputs.
+    pub fn restore(
+        snapshot: &Snapshot<T>,
+        registry: &Registry<'a, T, E>,
+    ) -> Result<Self, SnapshotError>
+    where
+        T: 'a,
+        E: 'a,
+    {
+        let mut reactor = Self {
+            cells: SimpleDAG::with_generations(snapshot.generations.clone()),
+            groups: Callbacks::new(),
+            history: None,
+        };
+
+        for cell in &snapshot.cells {
+            let (id, cell, parents) = match cell {
+                CellSnapshot::Input { id, value } => {
+                    let cell = Cell {
+                        value: Ok(value.clone()),
+                        compute: None,
+                        callbacks: Callbacks::new(),
+                    };
+                    (CellID::Input(*id), cell, Vec::new())
+                }
+                CellSnapshot::Compute {
+                    id,
+                    dependencies,
+                    function,
+                } => {
+                    let f = registry.get(function)?;
+                    let states = reactor
+                        .get_states(dependencies)
+                        .map_err(SnapshotError::MissingDependency)?;
+                    let cell = Cell {
+                        value: evaluate(&f, states),
+                        compute: Some(Compute {
+                            name: Some(function.clone()),
+                            ..Compute::new(dependencies, f)
+                        }),
+                        callbacks: Callbacks::new(),
+                    };
+                    let parents = dependencies.iter().map(CellID::raw_id).collect();
+                    (CellID::Compute(*id), cell, parents)
+                }
+            };
+            if !reactor.cells.insert(id.raw_id(), cell, &parents) {
+                return Err(SnapshotError::InvalidCell(id));
+            }
+        }
+
+        Ok(reactor)
+    }
+}
@@ -0,0 +1,218 @@
+//! A `Reactor` which can be shared across threads.
+//!
+//! The reactor itself never leaves the thread it lives on, every call
+//! is sent there as a job over a channel and runs to completion before
+//! the next one starts. This serializes concurrent `set_value` calls
+//! and lets readers see the state between two jobs only.
+
+use crate::{
+    CallbackID, CellID, ComputeCellID, InputCellID, Overflow, Reactor, RemoveCallbackError,
+    Subscription, Transaction, Updates,
+};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+type Job<T> = Box<dyn FnOnce(&mut Reactor<'static, T>) + Send>;
+
+/// `SyncReactor` is a cheap to clone handle to a `Reactor` running on its own thread.
+///
+/// The thread stops once the last handle is dropped, or on `shutdown`.
+/// The handles moved into the compute functions or the callbacks of
+/// the reactor count as handles too, a reactor holding some of them
+/// has to be shut down for its thread to stop.
+pub struct SyncReactor<T> {
+    // None stops the thread
+    jobs: mpsc::Sender<Option<Job<T>>>,
+    thread: thread::ThreadId,
+    // taken by the first `shutdown`
+    join: Arc<Mutex<Option<thread::JoinHandle<()>>>>,
+}
+
+impl<T> Clone for SyncReactor<T> {
+    fn clone(&self) -> Self {
+        Self {
+            jobs: self.jobs.clone(),
+            thread: self.thread,
+            join: Arc::clone(&self.join),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + Send + 'static> Default for SyncReactor<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + PartialEq + Send + 'static> SyncReactor<T> {
+    pub fn new() -> Self {
+        let (jobs, rx) = mpsc::channel::<Option<Job<T>>>();
+        let join = thread::spawn(move || {
+            let mut reactor = Reactor::new();
+            for job in rx.iter().map_while(|job| job) {
+                job(&mut reactor);
+            }
+        });
+        Self {
+            jobs,
+            thread: join.thread().id(),
+            join: Arc::new(Mutex::new(Some(join))),
+        }
+    }
+
+    // Stops the reactor thread once the calls made so far are done,
+    // and waits for it, the reactor being dropped together with the handles
+    // held by its compute functions and callbacks.
+    //
+    // Any later call through any of the handles panics, as it does once
+    // the thread is gone. Shutting down again does nothing.
+    //
+    // Panics if called on the reactor thread, see `with`.
+    pub fn shutdown(&self) {
+        assert!(
+            thread::current().id() != self.thread,
+            "the reactor shut down from its own thread"
+        );
+        // the lock is held until the thread is gone,
+        // so that all the calls return after that
+        let mut join = self.join.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(join) = join.take() {
+            // the thread may be gone already
+            let _ = self.jobs.send(None);
+            // a compute function or a callback which panicked stopped it already
+            let _ = join.join();
+        }
+    }
+
+    // Runs `f` against the reactor on its thread and waits for the result.
+    //
+    // Anything not covered by the other methods can be done this way,
+    // serialized with all the other calls the same way they are.
+    //
+    // Panics if the reactor thread is gone, which happens only after
+    // `shutdown` or if a compute function or a callback has panicked.
+    //
+    // Panics as well if called on the reactor thread, from a compute function
+    // or a callback, as the job would then wait for itself forever.
+    pub fn with<R, F>(&self, f: F) -> R
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Reactor<'static, T>) -> R + Send + 'static,
+    {
+        assert!(
+            thread::current().id() != self.thread,
+            "the reactor called from its own thread"
+        );
+        let (tx, rx) = mpsc::channel();
+        self.jobs
+            .send(Some(Box::new(move |reactor| {
+                // the caller can not go away while waiting for this
+                let _ = tx.send(f(reactor));
+            })))
+            .expect("reactor thread is gone");
+        rx.recv().expect("reactor thread is gone")
+    }
+
+    // See `Reactor::create_input`.
+    pub fn create_input(&self, initial: T) -> InputCellID {
+        self.with(move |r| r.create_input(initial))
+    }
+
+    // See `Reactor::create_compute`.
+    pub fn create_compute<F: Fn(&[T]) -> T + Send + Sync + 'static>(
+        &self,
+        dependencies: &[CellID],
+        f: F,
+    ) -> Result<ComputeCellID, CellID> {
+        let dependencies = dependencies.to_vec();
+        self.with(move |r| r.create_compute(&dependencies, f))
+    }
+
+    // See `Reactor::create_compute_ref`.
+    pub fn create_compute_ref<F: Fn(&[&T]) -> T + Send + Sync + 'static>(
+        &self,
+        dependencies: &[CellID],
+        f: F,
+    ) -> Result<ComputeCellID, CellID> {
+        let dependencies = dependencies.to_vec();
+        self.with(move |r| r.create_compute_ref(&dependencies, f))
+    }
+
+    // See `Reactor::value`.
+    pub fn value(&self, id: CellID) -> Option<T> {
+        self.with(move |r| r.value(id))
+    }
+
+    // Retrieves the values of many cells at once, all from the same stable state.
+    pub fn values(&self, ids: &[CellID]) -> Vec<Option<T>> {
+        let ids = ids.to_vec();
+        self.with(move |r| ids.into_iter().map(|id| r.value(id)).collect())
+    }
+
+    // See `Reactor::set_value`.
+    pub fn set_value(&self, id: InputCellID, new_value: T) -> bool {
+        self.with(move |r| r.set_value(id, new_value))
+    }
+
+    // See `Reactor::batch`.
+    pub fn batch<R, F>(&self, f: F) -> R
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Transaction<'_, 'static, T>) -> R + Send + 'static,
+    {
+        self.with(move |r| r.batch(f))
+    }
+
+    // See `Reactor::add_callback`.
+    //
+    // Callbacks run on the reactor thread.
+    pub fn add_callback<F: FnMut(T) + Send + Sync + 'static>(
+        &self,
+        id: impl Into<CellID>,
+        callback: F,
+    ) -> Option<CallbackID> {
+        let id = id.into();
+        self.with(move |r| r.add_callback(id, callback))
+    }
+
+    // See `Reactor::add_scoped_callback`.
+    //
+    // The subscription can be dropped on any thread.
+    pub fn add_scoped_callback<F: FnMut(T) + Send + Sync + 'static>(
+        &self,
+        id: impl Into<CellID>,
+        callback: F,
+    ) -> Option<Subscription> {
+        let id = id.into();
+        self.with(move |r| r.add_scoped_callback(id, callback))
+    }
+
+    // See `Reactor::remove_callback`.
+    pub fn remove_callback(
+        &self,
+        cell_id: impl Into<CellID>,
+        callback_id: CallbackID,
+    ) -> Result<(), RemoveCallbackError> {
+        let cell_id = cell_id.into();
+        self.with(move |r| r.remove_callback(cell_id, callback_id))
+    }
+
+    // See `Reactor::subscribe`.
+    pub fn subscribe(&self, id: impl Into<CellID>) -> Option<Updates<T>> {
+        let id = id.into();
+        self.with(move |r| r.subscribe(id))
+    }
+
+    // See `Reactor::subscribe_with`.
+    pub fn subscribe_with(
+        &self,
+        id: impl Into<CellID>,
+        capacity: usize,
+        overflow: Overflow,
+    ) -> Option<Updates<T>> {
+        // better panic here than on the reactor thread
+        assert!(capacity > 0, "`capacity` must be positive");
+        let id = id.into();
+        self.with(move |r| r.subscribe_with(id, capacity, overflow))
+    }
+}
@@ -0,0 +1,428 @@
+//! A reactor which cells are not bound to a single value type.
+//!
+//! Values live in the graph as `Box<dyn Any>` and only the typed handles
+//! remember what is inside, so a compute cell can turn a number into
+//! a `String` while the compiler still checks every read and write.
+
+use crate::callbacks::Callbacks;
+use crate::dag::{NodeID, Scheduler, SimpleDAG};
+use crate::{CallbackID, RemoveCallbackError};
+use std::any::Any;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// `InputCell<T>` is a handle to an input cell holding a `T`.
+pub struct InputCell<T> {
+    id: NodeID,
+    // the `TypedReactor::id` of the reactor it belongs to
+    reactor: usize,
+    // fn() -> T keeps the handle Copy, Send and Sync whatever T is
+    _type: PhantomData<fn() -> T>,
+}
+
+/// `ComputeCell<T>` is a handle to a compute cell holding a `T`.
+/// Neither the kind nor the type of a cell handle can be mixed up,
+/// demonstrated by the following tests:
+///
+/// ```compile_fail
+/// let mut r = react::TypedReactor::new();
+/// let input: react::ComputeCell<i32> = r.create_input(111);
+/// ```
+///
+/// ```compile_fail
+/// let mut r = react::TypedReactor::new();
+/// let input = r.create_input(111);
+/// let compute = r.create_compute(input, |v: &i32| v.to_string()).unwrap();
+/// r.set_value(compute, String::from("222"));
+/// ```
+///
+/// ```compile_fail
+/// let mut r = react::TypedReactor::new();
+/// let input = r.create_input(111);
+/// r.set_value(input, "222");
+/// ```
+pub struct ComputeCell<T> {
+    id: NodeID,
+    reactor: usize,
+    _type: PhantomData<fn() -> T>,
+}
+
+/// `TypedCell<T>` is either kind of cell holding a `T`,
+/// it is what compute cells accept as dependencies.
+pub enum TypedCell<T> {
+    Input(InputCell<T>),
+    Compute(ComputeCell<T>),
+}
+
+/// `TypedCallbackID` is a unique identifier for a callback of a cell
+/// of a `TypedReactor`, it's never valid for any other reactor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TypedCallbackID {
+    callback: CallbackID,
+    reactor: usize,
+}
+
+// derives would require T: Clone and friends, the handles do not need it
+macro_rules! impl_handle {
+    ($name:ident) => {
+        impl<T> $name<T> {
+            fn new(id: NodeID, reactor: usize) -> Self {
+                Self {
+                    id,
+                    reactor,
+                    _type: PhantomData,
+                }
+            }
+        }
+
+        impl<T> Clone for $name<T> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<T> Copy for $name<T> {}
+
+        impl<T> PartialEq for $name<T> {
+            fn eq(&self, other: &Self) -> bool {
+                self.id == other.id && self.reactor == other.reactor
+            }
+        }
+
+        impl<T> fmt::Debug for $name<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.id).finish()
+            }
+        }
+    };
+}
+
+impl_handle!(InputCell);
+impl_handle!(ComputeCell);
+
+impl<T> Clone for TypedCell<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TypedCell<T> {}
+
+impl<T> fmt::Debug for TypedCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Input(id) => f.debug_tuple("Input").field(id).finish(),
+            Self::Compute(id) => f.debug_tuple("Compute").field(id).finish(),
+        }
+    }
+}
+
+impl<T> From<InputCell<T>> for TypedCell<T> {
+    fn from(cell: InputCell<T>) -> Self {
+        Self::Input(cell)
+    }
+}
+
+impl<T> From<ComputeCell<T>> for TypedCell<T> {
+    fn from(cell: ComputeCell<T>) -> Self {
+        Self::Compute(cell)
+    }
+}
+
+type AnyComputeFn<'a> = Box<dyn Fn(&[&dyn Any]) -> Box<dyn Any> + 'a>;
+type AnyCallback<'a> = Box<dyn FnMut(&dyn Any) + 'a>;
+
+struct Compute<'a> {
+    dependencies: Vec<NodeID>,
+    f: AnyComputeFn<'a>,
+    callbacks: Callbacks<AnyCallback<'a>>,
+}
+
+struct Cell<'a> {
+    value: Box<dyn Any>,
+    // `PartialEq` of the concrete type, captured while it is still known
+    eq: fn(&dyn Any, &dyn Any) -> bool,
+    // None for an input cell
+    compute: Option<Compute<'a>>,
+}
+
+fn eq<T: PartialEq + 'static>(a: &dyn Any, b: &dyn Any) -> bool {
+    a.downcast_ref::<T>() == b.downcast_ref::<T>()
+}
+
+fn downcast<T: 'static>(value: &dyn Any) -> &T {
+    // handles are typed and checked on creation, so this is a bug if it fails
+    value
+        .downcast_ref()
+        .expect("cell holds a value of another type")
+}
+
+/// `TypedReactor` is a `Reactor` which cells may each hold a different type.
+///
+/// ```
+/// let mut r = react::TypedReactor::new();
+/// let celsius = r.create_input(21.5);
+/// let label = r
+///     .create_compute(celsius, |c: &f64| format!("{:.0}°C", c))
+///     .unwrap();
+/// r.set_value(celsius, -3.0);
+/// assert_eq!(r.value(label), Some(String::from("-3°C")));
+/// ```
+pub struct TypedReactor<'a> {
+    cells: SimpleDAG<Cell<'a>>,
+    // unique among all the reactors, so that the handles of
+    // the other ones never resolve here
+    id: usize,
+}
+
+impl Default for TypedReactor<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> TypedReactor<'a> {
+    pub fn new() -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        Self {
+            cells: SimpleDAG::new(),
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    // Creates an input cell with the specified initial value, returning its handle.
+    pub fn create_input<T: PartialEq + 'static>(&mut self, initial: T) -> InputCell<T> {
+        let cell = Cell {
+            value: Box::new(initial),
+            eq: eq::<T>,
+            compute: None,
+        };
+        InputCell::new(self.cells.add(cell, &[]), self.id)
+    }
+
+    // Creates a compute cell deriving its value from a single dependency.
+    //
+    // Returns None if the dependency does not exist in this reactor,
+    // same goes for the other `create_compute*` methods.
+    pub fn create_compute<A, U, F>(
+        &mut self,
+        a: impl Into<TypedCell<A>>,
+        f: F,
+    ) -> Option<ComputeCell<U>>
+    where
+        A: 'static,
+        U: PartialEq + 'static,
+        F: Fn(&A) -> U + 'a,
+    {
+        let a = self.resolve(a.into())?;
+        self.add_compute(vec![a], move |values| f(downcast(values[0])))
+    }
+
+    // Creates a compute cell deriving its value from two dependencies
+    // of possibly different types.
+    pub fn create_compute2<A, B, U, F>(
+        &mut self,
+        a: impl Into<TypedCell<A>>,
+        b: impl Into<TypedCell<B>>,
+        f: F,
+    ) -> Option<ComputeCell<U>>
+    where
+        A: 'static,
+        B: 'static,
+        U: PartialEq + 'static,
+        F: Fn(&A, &B) -> U + 'a,
+    {
+        let a = self.resolve(a.into())?;
+        let b = self.resolve(b.into())?;
+        self.add_compute(vec![a, b], move |values| {
+            f(downcast(values[0]), downcast(values[1]))
+        })
+    }
+
+    // Creates a compute cell deriving its value from three dependencies
+    // of possibly different types.
+    pub fn create_compute3<A, B, C, U, F>(
+        &mut self,
+        a: impl Into<TypedCell<A>>,
+        b: impl Into<TypedCell<B>>,
+        c: impl Into<TypedCell<C>>,
+        f: F,
+    ) -> Option<ComputeCell<U>>
+    where
+        A: 'static,
+        B: 'static,
+        C: 'static,
+        U: PartialEq + 'static,
+        F: Fn(&A, &B, &C) -> U + 'a,
+    {
+        let a = self.resolve(a.into())?;
+        let b = self.resolve(b.into())?;
+        let c = self.resolve(c.into())?;
+        self.add_compute(vec![a, b, c], move |values| {
+            f(
+                downcast(values[0]),
+                downcast(values[1]),
+                downcast(values[2]),
+            )
+        })
+    }
+
+    // Creates a compute cell over any number of dependencies of the same type.
+    pub fn create_compute_all<A, U, F>(
+        &mut self,
+        dependencies: &[TypedCell<A>],
+        f: F,
+    ) -> Option<ComputeCell<U>>
+    where
+        A: 'static,
+        U: PartialEq + 'static,
+        F: Fn(&[&A]) -> U + 'a,
+    {
+        let parents = dependencies
+            .iter()
+            .map(|&d| self.resolve(d))
+            .collect::<Option<_>>()?;
+        self.add_compute(parents, move |values| {
+            let values: Vec<&A> = values.iter().map(|&v| downcast(v)).collect();
+            f(&values)
+        })
+    }
+
+    fn add_compute<U, F>(&mut self, parents: Vec<NodeID>, f: F) -> Option<ComputeCell<U>>
+    where
+        U: PartialEq + 'static,
+        F: Fn(&[&dyn Any]) -> U + 'a,
+    {
+        let f: AnyComputeFn<'a> = Box::new(move |values| Box::new(f(values)));
+        let value = f(&self.get_values(&parents));
+
+        let cell = Cell {
+            value,
+            eq: eq::<U>,
+            compute: Some(Compute {
+                dependencies: parents.clone(),
+                f,
+                callbacks: Callbacks::new(),
+            }),
+        };
+        Some(ComputeCell::new(self.cells.add(cell, &parents), self.id))
+    }
+
+    // Finds the node behind the handle, making sure it is one of this reactor
+    // and has the right kind and type.
+    fn resolve<T: 'static>(&self, cell: TypedCell<T>) -> Option<NodeID> {
+        let (id, reactor, is_input) = match cell {
+            TypedCell::Input(cell) => (cell.id, cell.reactor, true),
+            TypedCell::Compute(cell) => (cell.id, cell.reactor, false),
+        };
+        if reactor != self.id {
+            return None;
+        }
+        let cell = self.cells.get(id)?;
+        if cell.compute.is_none() == is_input && cell.value.is::<T>() {
+            Some(id)
+        } else {
+            None
+        }
+    }
+
+    fn get_values(&self, ids: &[NodeID]) -> Vec<&dyn Any> {
+        ids.iter()
+            .map(|&id| self.cells.get(id).unwrap().value.as_ref())
+            .collect()
+    }
+
+    // Retrieves the current value of the cell, or None if the cell does not exist.
+    pub fn value<T: Clone + 'static>(&self, cell: impl Into<TypedCell<T>>) -> Option<T> {
+        let id = self.resolve(cell.into())?;
+        Some(downcast::<T>(self.cells.get(id)?.value.as_ref()).clone())
+    }
+
+    // Sets the value of the specified input cell.
+    //
+    // Returns false if the cell does not exist.
+    pub fn set_value<T: PartialEq + 'static>(&mut self, cell: InputCell<T>, new_value: T) -> bool {
+        let id = match self.resolve(cell.into()) {
+            Some(id) => id,
+            None => return false,
+        };
+
+        let value = &mut self.cells.get_mut(id).unwrap().value;
+        if *downcast::<T>(value.as_ref()) != new_value {
+            *value = Box::new(new_value);
+            self.propagate_change(id);
+        }
+        true
+    }
+
+    // Same as `Reactor::propagate_change`, just with the values type-erased.
+    fn propagate_change(&mut self, root: NodeID) {
+        let mut scheduler = Scheduler::new();
+        scheduler.schedule_children(&self.cells, root);
+
+        while let Some(id) = scheduler.pop() {
+            if self.update_compute(id) == Some(true) {
+                scheduler.schedule_children(&self.cells, id);
+            }
+        }
+    }
+
+    fn update_compute(&mut self, id: NodeID) -> Option<bool> {
+        let cell = self.cells.get(id)?;
+        let compute = cell.compute.as_ref()?;
+        let new_value = (compute.f)(&self.get_values(&compute.dependencies));
+        if (cell.eq)(new_value.as_ref(), cell.value.as_ref()) {
+            return Some(false);
+        }
+
+        let cell = self.cells.get_mut(id)?;
+        cell.value = new_value;
+        let value = cell.value.as_ref();
+        let callbacks = &mut cell.compute.as_mut()?.callbacks;
+        callbacks.for_each(|cb| cb(value));
+        Some(true)
+    }
+
+    // Adds a callback to the specified compute cell.
+    //
+    // Returns the ID of the just-added callback, or None if the cell doesn't exist.
+    pub fn add_callback<T: 'static, F: FnMut(&T) + 'a>(
+        &mut self,
+        cell: ComputeCell<T>,
+        mut callback: F,
+    ) -> Option<TypedCallbackID> {
+        let id = self.resolve(cell.into())?;
+        let callbacks = &mut self.cells.get_mut(id)?.compute.as_mut()?.callbacks;
+        let key = callbacks.add(Box::new(move |value| callback(downcast(value))), None);
+        Some(TypedCallbackID {
+            callback: CallbackID::new(id, key),
+            reactor: self.id,
+        })
+    }
+
+    // Removes the specified callback, using an ID returned from add_callback.
+    //
+    // Returns an Err if either the cell or callback does not exist.
+    pub fn remove_callback<T: 'static>(
+        &mut self,
+        cell: ComputeCell<T>,
+        callback_id: TypedCallbackID,
+    ) -> Result<(), RemoveCallbackError> {
+        let id = self
+            .resolve(cell.into())
+            .ok_or(RemoveCallbackError::NonexistentCell)?;
+        if callback_id.reactor != self.id || !callback_id.callback.belongs_to(id) {
+            return Err(RemoveCallbackError::NonexistentCallback);
+        }
+
+        let compute = self
+            .cells
+            .get_mut(id)
+            .and_then(|cell| cell.compute.as_mut());
+        compute
+            .and_then(|compute| compute.callbacks.remove(callback_id.callback.key()))
+            .map(|_| ())
+            .ok_or(RemoveCallbackError::NonexistentCallback)
+    }
+}
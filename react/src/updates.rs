@@ -0,0 +1,163 @@
+//! Compute cell changes delivered through a bounded queue
+//! instead of a callback, for consumers living on other threads.
+
+use crate::Subscription;
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// `Overflow` decides what happens when a new value arrives
+/// while the queue of a slow consumer is full.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Overflow {
+    /// The oldest queued value is dropped to make room for the new one.
+    DropOldest,
+    /// The queued values are all replaced by the new one,
+    /// so the consumer skips straight to the latest state.
+    KeepLatest,
+}
+
+struct Queue<T> {
+    values: VecDeque<T>,
+    capacity: usize,
+    overflow: Overflow,
+    // the publisher is gone, no more values will come
+    closed: bool,
+    // the receiver is gone, no point in queueing
+    abandoned: bool,
+}
+
+struct Shared<T> {
+    queue: Mutex<Queue<T>>,
+    ready: Condvar,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, Queue<T>> {
+        // nothing can panic while the lock is held
+        self.queue.lock().unwrap()
+    }
+}
+
+/// `Updates` receives the values of a compute cell after each propagation
+/// that changed it, see `Reactor::subscribe`.
+///
+/// Iterating blocks until the next value and ends once the cell
+/// or the reactor it belongs to is gone.
+pub struct Updates<T> {
+    shared: Arc<Shared<T>>,
+    // the publishing callback goes away together with the receiver
+    _subscription: Subscription,
+}
+
+// The reactor side of `Updates`, lives in a callback.
+pub(crate) struct Publisher<T>(Arc<Shared<T>>);
+
+pub(crate) fn channel<T>(
+    capacity: usize,
+    overflow: Overflow,
+    subscription: Subscription,
+) -> (Publisher<T>, Updates<T>) {
+    assert!(capacity > 0, "`capacity` must be positive");
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(Queue {
+            values: VecDeque::with_capacity(capacity),
+            capacity,
+            overflow,
+            closed: false,
+            abandoned: false,
+        }),
+        ready: Condvar::new(),
+    });
+    let updates = Updates {
+        shared: Arc::clone(&shared),
+        _subscription: subscription,
+    };
+    (Publisher(shared), updates)
+}
+
+impl<T> Publisher<T> {
+    pub(crate) fn publish(&self, value: T) {
+        let mut queue = self.0.lock();
+        if queue.abandoned {
+            return;
+        }
+        if queue.values.len() == queue.capacity {
+            match queue.overflow {
+                Overflow::DropOldest => {
+                    queue.values.pop_front();
+                }
+                Overflow::KeepLatest => queue.values.clear(),
+            }
+        }
+        queue.values.push_back(value);
+        self.0.ready.notify_one();
+    }
+}
+
+impl<T> Drop for Publisher<T> {
+    fn drop(&mut self) {
+        self.0.lock().closed = true;
+        self.0.ready.notify_one();
+    }
+}
+
+impl<T> Updates<T> {
+    // Takes the next value if there is one queued already.
+    pub fn try_recv(&self) -> Option<T> {
+        self.shared.lock().values.pop_front()
+    }
+
+    // Waits for the next value.
+    //
+    // Returns None once the queue is drained and no more values can come.
+    pub fn recv(&self) -> Option<T> {
+        let mut queue = self.shared.lock();
+        loop {
+            if let Some(value) = queue.values.pop_front() {
+                return Some(value);
+            }
+            if queue.closed {
+                return None;
+            }
+            queue = self.shared.ready.wait(queue).unwrap();
+        }
+    }
+
+    // Same as `recv`, but gives up after `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<T> {
+        let deadline = Instant::now() + timeout;
+        let mut queue = self.shared.lock();
+        loop {
+            if let Some(value) = queue.values.pop_front() {
+                return Some(value);
+            }
+            let now = Instant::now();
+            if queue.closed || now >= deadline {
+                return None;
+            }
+            queue = self
+                .shared
+                .ready
+                .wait_timeout(queue, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+}
+
+impl<T> Iterator for Updates<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.recv()
+    }
+}
+
+impl<T> Drop for Updates<T> {
+    fn drop(&mut self) {
+        let mut queue = self.shared.lock();
+        queue.abandoned = true;
+        queue.values.clear();
+    }
+}
@@ -0,0 +1,169 @@
+use react::*;
+
+#[test]
+fn dependencies_and_dependents() {
+    let mut reactor = Reactor::new();
+    let a = reactor.create_input(1);
+    let b = reactor.create_input(2);
+    let sum = reactor
+        .create_compute(&[CellID::Input(a), CellID::Input(b)], |v| v[0] + v[1])
+        .unwrap();
+    let double = reactor
+        .create_compute(&[CellID::Compute(sum), CellID::Compute(sum)], |v| {
+            v[0] + v[1]
+        })
+        .unwrap();
+
+    assert_eq!(reactor.dependencies_of(CellID::Input(a)), Some(vec![]));
+    assert_eq!(
+        reactor.dependencies_of(CellID::Compute(sum)),
+        Some(vec![CellID::Input(a), CellID::Input(b)])
+    );
+    assert_eq!(reactor.dependents_of(CellID::Input(a)), Some(vec![sum]));
+    assert_eq!(
+        reactor.dependents_of(CellID::Compute(sum)),
+        Some(vec![double])
+    );
+    assert_eq!(reactor.dependents_of(CellID::Compute(double)), Some(vec![]));
+
+    assert_eq!(reactor.depth_of(CellID::Input(b)), Some(0));
+    assert_eq!(reactor.depth_of(CellID::Compute(double)), Some(2));
+}
+
+#[test]
+fn introspecting_a_nonexistent_cell() {
+    let mut dummy_reactor = Reactor::new();
+    let input = dummy_reactor.create_input(1);
+    let output = dummy_reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0])
+        .unwrap();
+
+    let reactor: Reactor<i32> = Reactor::new();
+    assert_eq!(reactor.dependencies_of(CellID::Input(input)), None);
+    assert_eq!(reactor.dependents_of(CellID::Compute(output)), None);
+    assert_eq!(reactor.depth_of(CellID::Input(input)), None);
+    assert_eq!(reactor.recompute_count(output), None);
+}
+
+#[test]
+fn topological_order_puts_dependencies_first() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let deep = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0])
+        .unwrap();
+    let deeper = reactor
+        .create_compute(&[CellID::Compute(deep)], |v| v[0])
+        .unwrap();
+    // created last, but shallower than `deeper`
+    let late = reactor.create_input(2);
+    let order = reactor.topological_order();
+    assert_eq!(order.len(), 4);
+    let position = |id: CellID| order.iter().position(|&c| c == id).unwrap();
+    assert!(position(CellID::Input(input)) < position(CellID::Compute(deep)));
+    assert!(position(CellID::Compute(deep)) < position(CellID::Compute(deeper)));
+    assert!(position(CellID::Input(late)) < position(CellID::Compute(deeper)));
+}
+
+#[test]
+fn recompute_count_includes_unchanged_values() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let parity = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] % 2)
+        .unwrap();
+    let output = reactor
+        .create_compute(&[CellID::Compute(parity)], |v| v[0] * 10)
+        .unwrap();
+    assert_eq!(reactor.recompute_count(parity), Some(0));
+
+    reactor.set_value(input, 3);
+    reactor.set_value(input, 4);
+    assert_eq!(reactor.recompute_count(parity), Some(2));
+    // the parity stayed the same once
+    assert_eq!(reactor.recompute_count(output), Some(1));
+}
+
+#[test]
+fn dot_export_draws_cells_and_edges() {
+    let mut registry = Registry::new();
+    registry.register("plus_one", |v: &[i32]| v[0] + 1);
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    reactor
+        .create_registered(&registry, "plus_one", &[CellID::Input(input)])
+        .unwrap();
+    reactor.set_value(input, 2);
+
+    assert_eq!(
+        reactor.to_dot(),
+        "digraph reactor {\n\
+         \x20   cell0 [shape=box, label=\"#0 = 2\"];\n\
+         \x20   cell1 [shape=ellipse, label=\"#1 = 3\\nplus_one, recomputed 1\"];\n\
+         \x20   cell0 -> cell1;\n\
+         }\n"
+    );
+}
+
+#[test]
+fn dot_export_shows_errors() {
+    let mut reactor = Reactor::new_fallible();
+    let input = reactor.create_input(0);
+    reactor
+        .create_try_compute(&[CellID::Input(input)], |v| {
+            10i32.checked_div(v[0]).ok_or("division by zero")
+        })
+        .unwrap();
+    assert!(reactor
+        .to_dot()
+        .contains("label=\"#1 = Err(\\\"division by zero\\\")\\nfn, recomputed 0\""));
+}
+
+#[test]
+fn dot_export_leaves_dirty_lazy_cells_alone() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let lazy = reactor
+        .create_lazy_compute(&[CellID::Input(input)], |v| v[0] * 2)
+        .unwrap();
+    reactor.set_value(input, 2);
+
+    assert!(reactor
+        .to_dot()
+        .contains("label=\"#1 = dirty\\nfn, recomputed 0\""));
+    assert_eq!(reactor.recompute_count(lazy), Some(0));
+    assert_eq!(reactor.value(CellID::Compute(lazy)), Some(4));
+}
+
+#[test]
+fn recompute_count_leaves_out_errors_passed_along() {
+    let calls = std::cell::Cell::new(0);
+    let mut reactor = Reactor::new_fallible();
+    let input = reactor.create_input(1);
+    let root = reactor
+        .create_try_compute(&[CellID::Input(input)], |v| {
+            10i32.checked_div(v[0]).ok_or("division by zero")
+        })
+        .unwrap();
+    let eager = reactor
+        .create_compute(&[CellID::Compute(root)], |v| {
+            calls.set(calls.get() + 1);
+            v[0] + 1
+        })
+        .unwrap();
+    let lazy = reactor
+        .create_lazy_compute(&[CellID::Compute(root)], |v| {
+            calls.set(calls.get() + 1);
+            v[0] + 2
+        })
+        .unwrap();
+    calls.set(0);
+
+    for value in [0, 2, 0, 5] {
+        reactor.set_value(input, value);
+        reactor.try_value(CellID::Compute(lazy));
+    }
+    assert_eq!(calls.get(), 4);
+    assert_eq!(reactor.recompute_count(eager), Some(2));
+    assert_eq!(reactor.recompute_count(lazy), Some(2));
+}
@@ -466,3 +466,1198 @@ fn test_adder_with_boolean_values() {
         );
     }
 }
+
+#[test]
+fn a_cell_without_dependents_can_be_removed() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    assert_eq!(reactor.remove_cell(CellID::Compute(output)), Ok(()));
+    assert_eq!(reactor.value(CellID::Compute(output)), None);
+    assert_eq!(reactor.remove_cell(CellID::Input(input)), Ok(()));
+    assert_eq!(reactor.value(CellID::Input(input)), None);
+    assert!(!reactor.set_value(input, 2));
+}
+
+#[test]
+fn error_removing_a_cell_with_dependents() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let plus_one = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    let double = reactor
+        .create_compute(&[CellID::Input(input), CellID::Input(input)], |v| {
+            v[0] + v[1]
+        })
+        .unwrap();
+    assert_eq!(
+        reactor.remove_cell(CellID::Input(input)),
+        Err(RemoveCellError::HasDependents(vec![plus_one, double]))
+    );
+    assert!(reactor.set_value(input, 2));
+    assert_eq!(reactor.value(CellID::Compute(double)), Some(4));
+}
+
+#[test]
+fn error_removing_a_nonexistent_cell() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    assert_eq!(reactor.remove_cell(CellID::Input(input)), Ok(()));
+    assert_eq!(
+        reactor.remove_cell(CellID::Input(input)),
+        Err(RemoveCellError::NonexistentCell)
+    );
+    assert_eq!(
+        reactor.remove_cell_cascade(CellID::Input(input)),
+        Err(RemoveCellError::NonexistentCell)
+    );
+}
+
+#[test]
+fn cascading_removal_removes_all_dependents() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let other = reactor.create_input(10);
+    let plus_one = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    let sum = reactor
+        .create_compute(&[CellID::Compute(plus_one), CellID::Input(other)], |v| {
+            v[0] + v[1]
+        })
+        .unwrap();
+    let unrelated = reactor
+        .create_compute(&[CellID::Input(other)], |v| v[0] * 2)
+        .unwrap();
+
+    assert_eq!(
+        reactor.remove_cell_cascade(CellID::Input(input)),
+        Ok(vec![sum, plus_one])
+    );
+    assert_eq!(reactor.value(CellID::Input(input)), None);
+    assert_eq!(reactor.value(CellID::Compute(plus_one)), None);
+    assert_eq!(reactor.value(CellID::Compute(sum)), None);
+
+    // the untouched part of the graph keeps working
+    assert!(reactor.set_value(other, 20));
+    assert_eq!(reactor.value(CellID::Compute(unrelated)), Some(40));
+    assert!(reactor.remove_cell(CellID::Input(other)).is_err());
+}
+
+#[test]
+fn stale_ids_do_not_alias_reused_cells() {
+    let mut reactor = Reactor::new();
+    let old_input = reactor.create_input(1);
+    let old_output = reactor
+        .create_compute(&[CellID::Input(old_input)], |v| v[0] + 1)
+        .unwrap();
+    reactor
+        .remove_cell_cascade(CellID::Input(old_input))
+        .unwrap();
+
+    let input = reactor.create_input(100);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] - 1)
+        .unwrap();
+    assert_eq!(reactor.value(CellID::Input(old_input)), None);
+    assert_eq!(reactor.value(CellID::Compute(old_output)), None);
+    assert!(!reactor.set_value(old_input, 5));
+    assert_eq!(reactor.add_callback(old_output, |_| ()), None);
+    assert_eq!(reactor.value(CellID::Input(input)), Some(100));
+    assert_eq!(reactor.value(CellID::Compute(output)), Some(99));
+}
+
+#[test]
+fn removed_cells_stop_firing_callbacks() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let plus_one = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    let plus_two = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] + 2)
+        .unwrap();
+    assert!(reactor
+        .add_callback(plus_two, |v| cb.callback_called(v))
+        .is_some());
+    assert_eq!(reactor.remove_cell(CellID::Compute(plus_one)), Ok(()));
+
+    assert!(reactor.set_value(input, 2));
+    cb.expect_to_have_been_called_with(4);
+    assert_eq!(reactor.remove_cell(CellID::Compute(plus_two)), Ok(()));
+    assert!(reactor.set_value(input, 3));
+    cb.expect_not_to_have_been_called();
+}
+
+#[test]
+fn batched_changes_fire_callbacks_once_with_the_final_value() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let a = reactor.create_input(1);
+    let b = reactor.create_input(2);
+    let sum = reactor
+        .create_compute(&[CellID::Input(a), CellID::Input(b)], |v| v[0] + v[1])
+        .unwrap();
+    let double = reactor
+        .create_compute(&[CellID::Compute(sum)], |v| v[0] * 2)
+        .unwrap();
+    assert!(reactor
+        .add_callback(double, |v| cb.callback_called(v))
+        .is_some());
+
+    reactor.batch(|tx| {
+        assert!(tx.set(a, 10));
+        assert!(tx.set(b, 20));
+    });
+    cb.expect_to_have_been_called_with(60);
+    cb.expect_to_have_been_called_times(1);
+    assert_eq!(reactor.value(CellID::Compute(sum)), Some(30));
+}
+
+#[test]
+fn batched_changes_that_cancel_out_do_not_fire_callbacks() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let a = reactor.create_input(1);
+    let b = reactor.create_input(2);
+    let sum = reactor
+        .create_compute(&[CellID::Input(a), CellID::Input(b)], |v| v[0] + v[1])
+        .unwrap();
+    assert!(reactor
+        .add_callback(sum, |v| cb.callback_called(v))
+        .is_some());
+
+    reactor.batch(|tx| {
+        tx.set(a, 2);
+        tx.set(b, 1);
+    });
+    cb.expect_not_to_have_been_called();
+
+    reactor.batch(|tx| {
+        tx.set(a, 5);
+        tx.set(a, 2);
+    });
+    cb.expect_not_to_have_been_called();
+}
+
+#[test]
+fn error_setting_a_nonexistent_input_cell_in_a_batch() {
+    let mut dummy_reactor = Reactor::new();
+    let _ = dummy_reactor.create_input(1);
+    let dummy = dummy_reactor.create_input(1);
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let (set_input, set_dummy) = reactor.batch(|tx| (tx.set(input, 2), tx.set(dummy, 2)));
+    assert!(set_input);
+    assert!(!set_dummy);
+    assert_eq!(reactor.value(CellID::Input(input)), Some(2));
+}
+
+#[test]
+fn shared_descendants_are_recomputed_once_per_change() {
+    let calls = std::cell::Cell::new(0);
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let left = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    let right = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] - 1)
+        .unwrap();
+    let _ = reactor
+        .create_compute(&[CellID::Compute(left), CellID::Compute(right)], |v| {
+            calls.set(calls.get() + 1);
+            v[0] * v[1]
+        })
+        .unwrap();
+    calls.set(0);
+
+    assert!(reactor.set_value(input, 5));
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn cells_downstream_of_an_unchanged_value_are_not_recomputed() {
+    let calls = std::cell::Cell::new(0);
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let is_big = reactor
+        .create_compute(&[CellID::Input(input)], |v| (v[0] > 10) as i32)
+        .unwrap();
+    let _ = reactor
+        .create_compute(&[CellID::Compute(is_big)], |v| {
+            calls.set(calls.get() + 1);
+            v[0] * 100
+        })
+        .unwrap();
+    calls.set(0);
+
+    assert!(reactor.set_value(input, 2));
+    assert!(reactor.set_value(input, 3));
+    assert_eq!(calls.get(), 0);
+    assert!(reactor.set_value(input, 30));
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn non_copy_values_can_be_computed_from_references() {
+    let mut reactor = Reactor::new();
+    let first = reactor.create_input(String::from("Hello"));
+    let last = reactor.create_input(String::from("World"));
+    let greeting = reactor
+        .create_compute_ref(&[CellID::Input(first), CellID::Input(last)], |v| {
+            format!("{}, {}!", v[0], v[1])
+        })
+        .unwrap();
+    assert_eq!(
+        reactor.value(CellID::Compute(greeting)),
+        Some(String::from("Hello, World!"))
+    );
+
+    assert!(reactor.set_value(last, String::from("Rust")));
+    assert_eq!(
+        reactor.value(CellID::Compute(greeting)),
+        Some(String::from("Hello, Rust!"))
+    );
+}
+
+#[test]
+fn non_copy_values_can_be_computed_from_clones() {
+    let mut reactor = Reactor::new();
+    let list = reactor.create_input(vec![1, 2, 3]);
+    let reversed = reactor
+        .create_compute(&[CellID::Input(list)], |v| {
+            let mut list = v[0].clone();
+            list.reverse();
+            list
+        })
+        .unwrap();
+    assert!(reactor.set_value(list, vec![4, 5]));
+    assert_eq!(reactor.value(CellID::Compute(reversed)), Some(vec![5, 4]));
+}
+
+#[test]
+fn every_kind_of_compute_cell_can_borrow_the_values() {
+    let mut reactor = Reactor::new_fallible();
+    let input = reactor.create_input(String::from("a"));
+    let dependencies = [CellID::Input(input)];
+    let fallible = reactor
+        .create_try_compute_ref(&dependencies, |v| {
+            v[0].parse::<i32>()
+                .map(|_| v[0].clone())
+                .map_err(|_| v[0].len())
+        })
+        .unwrap();
+    let trimmed = reactor
+        .create_compute_with_ref(
+            &dependencies,
+            |v| v[0].trim().to_string(),
+            |a, b| a.eq_ignore_ascii_case(b),
+        )
+        .unwrap();
+    let lazy = reactor
+        .create_lazy_compute_ref(&dependencies, |v| v[0].repeat(2))
+        .unwrap();
+    reactor
+        .set_compute_ref(lazy, &[CellID::Compute(trimmed)], |v| v[0].repeat(3))
+        .unwrap();
+
+    assert!(reactor.set_value(input, String::from(" A ")));
+    assert_eq!(reactor.try_value(CellID::Compute(fallible)), Some(Err(3)));
+    assert_eq!(
+        reactor.value(CellID::Compute(trimmed)),
+        Some(String::from("a"))
+    );
+    assert_eq!(
+        reactor.value(CellID::Compute(lazy)),
+        Some(String::from("aaa"))
+    );
+    assert!(reactor.set_value(input, String::from("12")));
+    assert_eq!(
+        reactor.try_value(CellID::Compute(fallible)),
+        Some(Ok(String::from("12")))
+    );
+    assert_eq!(
+        reactor.value(CellID::Compute(lazy)),
+        Some(String::from("121212"))
+    );
+}
+
+#[test]
+fn reference_callbacks_see_the_final_value() {
+    let seen = std::cell::RefCell::new(Vec::new());
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(String::from("a"));
+    let upper = reactor
+        .create_compute_ref(&[CellID::Input(input)], |v| v[0].to_uppercase())
+        .unwrap();
+    assert!(reactor
+        .add_callback_ref(upper, |v: &String| seen.borrow_mut().push(v.clone()))
+        .is_some());
+
+    assert!(reactor.set_value(input, String::from("b")));
+    assert!(reactor.set_value(input, String::from("B")));
+    assert_eq!(*seen.borrow(), vec![String::from("B")]);
+}
+
+#[test]
+fn subscriptions_receive_the_final_values() {
+    let mut reactor = Reactor::new();
+    let a = reactor.create_input(1);
+    let b = reactor.create_input(2);
+    let sum = reactor
+        .create_compute(&[CellID::Input(a), CellID::Input(b)], |v| v[0] + v[1])
+        .unwrap();
+    let updates = reactor
+        .subscribe_with(sum, 10, Overflow::DropOldest)
+        .unwrap();
+
+    assert!(reactor.set_value(a, 2));
+    reactor.batch(|tx| {
+        tx.set(a, 10);
+        tx.set(b, 20);
+    });
+    assert!(reactor.set_value(a, 8));
+    assert!(reactor.set_value(b, 22));
+    assert_eq!(updates.try_recv(), Some(4));
+    assert_eq!(updates.try_recv(), Some(30));
+    assert_eq!(updates.try_recv(), Some(28));
+    assert_eq!(updates.try_recv(), Some(30));
+    assert_eq!(updates.try_recv(), None);
+}
+
+#[test]
+fn slow_subscribers_lose_the_oldest_values() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(0);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] * 10)
+        .unwrap();
+    let updates = reactor
+        .subscribe_with(output, 2, Overflow::DropOldest)
+        .unwrap();
+
+    for i in 1..=5 {
+        assert!(reactor.set_value(input, i));
+    }
+    assert_eq!(updates.try_recv(), Some(40));
+    assert_eq!(updates.try_recv(), Some(50));
+    assert_eq!(updates.try_recv(), None);
+}
+
+#[test]
+fn slow_subscribers_can_skip_to_the_latest_value() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(0);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] * 10)
+        .unwrap();
+    let latest = reactor.subscribe(output).unwrap();
+    let coalesced = reactor
+        .subscribe_with(output, 3, Overflow::KeepLatest)
+        .unwrap();
+
+    for i in 1..=5 {
+        assert!(reactor.set_value(input, i));
+    }
+    assert_eq!(latest.try_recv(), Some(50));
+    assert_eq!(latest.try_recv(), None);
+    assert_eq!(coalesced.try_recv(), Some(40));
+    assert_eq!(coalesced.try_recv(), Some(50));
+    assert_eq!(coalesced.try_recv(), None);
+}
+
+#[test]
+fn subscriptions_end_with_their_cell() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(0);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    let updates = reactor
+        .subscribe_with(output, 10, Overflow::DropOldest)
+        .unwrap();
+    assert!(reactor.set_value(input, 1));
+    assert_eq!(reactor.remove_cell(CellID::Compute(output)), Ok(()));
+    assert_eq!(updates.collect::<Vec<_>>(), vec![2]);
+}
+
+#[test]
+fn error_subscribing_to_nonexistent_cell() {
+    let mut dummy_reactor = Reactor::new();
+    let input = dummy_reactor.create_input(1);
+    let output = dummy_reactor
+        .create_compute(&[CellID::Input(input)], |_| 0)
+        .unwrap();
+    assert!(Reactor::<u32>::new().subscribe(output).is_none());
+}
+
+#[test]
+fn subscriptions_can_be_awaited_from_another_thread() {
+    let reactor = SyncReactor::new();
+    let input = reactor.create_input(0);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] * 2)
+        .unwrap();
+    let updates = reactor
+        .subscribe_with(output, 100, Overflow::DropOldest)
+        .unwrap();
+
+    let consumer = std::thread::spawn(move || {
+        updates
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .into_iter()
+            .chain(updates.take(2))
+            .collect::<Vec<_>>()
+    });
+    for i in 1..=3 {
+        assert!(reactor.set_value(input, i));
+    }
+    assert_eq!(consumer.join().unwrap(), vec![2, 4, 6]);
+}
+
+#[test]
+fn input_cells_fire_callbacks() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    assert!(reactor
+        .add_callback(input, |v| cb.callback_called(v))
+        .is_some());
+
+    assert!(reactor.set_value(input, 1));
+    cb.expect_not_to_have_been_called();
+    assert!(reactor.set_value(input, 2));
+    cb.expect_to_have_been_called_with(2);
+
+    reactor.batch(|tx| {
+        tx.set(input, 3);
+        tx.set(input, 4);
+    });
+    cb.expect_to_have_been_called_with(4);
+    cb.expect_to_have_been_called_times(2);
+}
+
+#[test]
+fn input_callbacks_can_be_removed() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let callback = reactor
+        .add_callback(CellID::Input(input), |v| cb.callback_called(v))
+        .unwrap();
+    assert_eq!(reactor.remove_callback(input, callback), Ok(()));
+    assert!(reactor.set_value(input, 2));
+    cb.expect_not_to_have_been_called();
+}
+
+#[test]
+fn group_callbacks_fire_once_per_change_with_all_values() {
+    let seen = std::cell::RefCell::new(Vec::new());
+    let mut reactor = Reactor::new();
+    let a = reactor.create_input(1);
+    let b = reactor.create_input(2);
+    let sum = reactor
+        .create_compute(&[CellID::Input(a), CellID::Input(b)], |v| v[0] + v[1])
+        .unwrap();
+    let unrelated = reactor.create_input(0);
+    assert!(reactor
+        .add_group_callback(
+            &[CellID::Input(a), CellID::Input(b), CellID::Compute(sum)],
+            |v| seen.borrow_mut().push(v.to_vec()),
+        )
+        .is_some());
+
+    assert!(reactor.set_value(a, 10));
+    reactor.batch(|tx| {
+        tx.set(a, 20);
+        tx.set(b, 30);
+    });
+    assert!(reactor.set_value(unrelated, 5));
+    assert_eq!(*seen.borrow(), vec![vec![10, 2, 12], vec![20, 30, 50]]);
+}
+
+#[test]
+fn group_callbacks_can_be_removed() {
+    let calls = std::cell::Cell::new(0);
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let callback = reactor
+        .add_group_callback(&[CellID::Input(input)], |_| calls.set(calls.get() + 1))
+        .unwrap();
+    assert!(reactor.set_value(input, 2));
+    assert_eq!(reactor.remove_group_callback(callback), Ok(()));
+    assert_eq!(
+        reactor.remove_group_callback(callback),
+        Err(RemoveCallbackError::NonexistentCallback)
+    );
+    assert!(reactor.set_value(input, 3));
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn group_callbacks_go_with_their_removed_cells() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    let watching_input = reactor
+        .add_group_callback(&[CellID::Input(input)], |_| ())
+        .unwrap();
+    let watching_output = reactor
+        .add_group_callback(&[CellID::Input(input), CellID::Compute(output)], |_| ())
+        .unwrap();
+
+    assert_eq!(reactor.remove_cell(CellID::Compute(output)), Ok(()));
+    assert_eq!(
+        reactor.remove_group_callback(watching_output),
+        Err(RemoveCallbackError::NonexistentCallback)
+    );
+    assert_eq!(reactor.remove_group_callback(watching_input), Ok(()));
+
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    let watching_output = reactor
+        .add_group_callback(&[CellID::Compute(output)], |_| ())
+        .unwrap();
+    assert_eq!(
+        reactor.remove_cell_cascade(CellID::Input(input)),
+        Ok(vec![output])
+    );
+    assert_eq!(
+        reactor.remove_group_callback(watching_output),
+        Err(RemoveCallbackError::NonexistentCallback)
+    );
+}
+
+#[test]
+fn error_adding_group_callback_with_nonexistent_cell() {
+    let mut dummy_reactor = Reactor::new();
+    let _ = dummy_reactor.create_input(1);
+    let dummy = dummy_reactor.create_input(1);
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    assert!(reactor
+        .add_group_callback(&[CellID::Input(input), CellID::Input(dummy)], |_| ())
+        .is_none());
+}
+
+#[test]
+fn custom_equality_suppresses_jitter() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(100);
+    let smoothed = reactor
+        .create_compute_with(
+            &[CellID::Input(input)],
+            |v| v[0],
+            |old: &i32, new: &i32| (old - new).abs() < 5,
+        )
+        .unwrap();
+    let doubled = reactor
+        .create_compute(&[CellID::Compute(smoothed)], |v| v[0] * 2)
+        .unwrap();
+    assert!(reactor
+        .add_callback(smoothed, |v| cb.callback_called(v))
+        .is_some());
+
+    assert!(reactor.set_value(input, 103));
+    cb.expect_not_to_have_been_called();
+    assert_eq!(reactor.value(CellID::Compute(smoothed)), Some(100));
+    assert_eq!(reactor.value(CellID::Compute(doubled)), Some(200));
+
+    // the drift adds up against the last kept value
+    assert!(reactor.set_value(input, 106));
+    cb.expect_to_have_been_called_with(106);
+    assert_eq!(reactor.value(CellID::Compute(doubled)), Some(212));
+}
+
+#[test]
+fn custom_equality_can_compare_floats_relatively() {
+    let changes = std::cell::RefCell::new(Vec::new());
+    let mut reactor = Reactor::new();
+    let reading = reactor.create_input(10.0);
+    let celsius = reactor
+        .create_compute_with(
+            &[CellID::Input(reading)],
+            |v| v[0],
+            // changed by less than 10%
+            |old: &f64, new: &f64| ((new - old) / old).abs() < 0.1,
+        )
+        .unwrap();
+    assert!(reactor
+        .add_callback(celsius, |v| changes.borrow_mut().push(v))
+        .is_some());
+
+    for &v in &[10.5, 10.9, 11.0, 11.5, 13.0] {
+        assert!(reactor.set_value(reading, v));
+    }
+    assert_eq!(*changes.borrow(), vec![11.0, 13.0]);
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum MathError {
+    DivisionByZero,
+    Negative(i32),
+}
+
+#[test]
+fn fallible_compute_cells_report_errors() {
+    let mut reactor = Reactor::new_fallible();
+    let a = reactor.create_input(10i32);
+    let b = reactor.create_input(2);
+    let quotient = reactor
+        .create_try_compute(&[CellID::Input(a), CellID::Input(b)], |v| {
+            v[0].checked_div(v[1]).ok_or(MathError::DivisionByZero)
+        })
+        .unwrap();
+    assert_eq!(reactor.try_value(CellID::Compute(quotient)), Some(Ok(5)));
+
+    assert!(reactor.set_value(b, 0));
+    assert_eq!(
+        reactor.try_value(CellID::Compute(quotient)),
+        Some(Err(MathError::DivisionByZero))
+    );
+    assert_eq!(reactor.value(CellID::Compute(quotient)), None);
+    assert_eq!(reactor.try_value(CellID::Input(b)), Some(Ok(0)));
+}
+
+#[test]
+fn fallible_compute_cells_can_fail_from_the_start() {
+    let mut reactor = Reactor::new_fallible();
+    let input = reactor.create_input(-1);
+    let checked = reactor
+        .create_try_compute(&[CellID::Input(input)], |v| {
+            if v[0] < 0 {
+                Err(MathError::Negative(v[0]))
+            } else {
+                Ok(v[0])
+            }
+        })
+        .unwrap();
+    assert_eq!(
+        reactor.try_value(CellID::Compute(checked)),
+        Some(Err(MathError::Negative(-1)))
+    );
+}
+
+#[test]
+fn errors_reach_dependents_without_recomputing_them() {
+    let calls = std::cell::Cell::new(0);
+    let mut reactor = Reactor::new_fallible();
+    let input = reactor.create_input(4);
+    let root = reactor
+        .create_try_compute(&[CellID::Input(input)], |v| {
+            if v[0] < 0 {
+                Err(MathError::Negative(v[0]))
+            } else {
+                Ok(v[0] * 10)
+            }
+        })
+        .unwrap();
+    let plus_one = reactor
+        .create_compute(&[CellID::Compute(root), CellID::Input(input)], |v| {
+            calls.set(calls.get() + 1);
+            v[0] + v[1]
+        })
+        .unwrap();
+    calls.set(0);
+
+    assert!(reactor.set_value(input, -3));
+    assert_eq!(calls.get(), 0);
+    assert_eq!(
+        reactor.try_value(CellID::Compute(plus_one)),
+        Some(Err(MathError::Negative(-3)))
+    );
+
+    assert!(reactor.set_value(input, -5));
+    assert_eq!(
+        reactor.try_value(CellID::Compute(plus_one)),
+        Some(Err(MathError::Negative(-5)))
+    );
+
+    // and everything recovers once the input is valid again
+    assert!(reactor.set_value(input, 3));
+    assert_eq!(calls.get(), 1);
+    assert_eq!(reactor.try_value(CellID::Compute(plus_one)), Some(Ok(33)));
+}
+
+#[test]
+fn callbacks_skip_errors_and_fire_on_recovery() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new_fallible();
+    let input = reactor.create_input(1);
+    let inverse = reactor
+        .create_try_compute(&[CellID::Input(input)], |v| {
+            100i32.checked_div(v[0]).ok_or(MathError::DivisionByZero)
+        })
+        .unwrap();
+    assert!(reactor
+        .add_callback(inverse, |v| cb.callback_called(v))
+        .is_some());
+
+    assert!(reactor.set_value(input, 0));
+    cb.expect_not_to_have_been_called();
+    assert!(reactor.set_value(input, 1));
+    cb.expect_to_have_been_called_with(100);
+}
+
+#[test]
+fn lazy_compute_cells_wait_until_read() {
+    let calls = std::cell::Cell::new(0);
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let lazy = reactor
+        .create_lazy_compute(&[CellID::Input(input)], |v| {
+            calls.set(calls.get() + 1);
+            v[0] * 10
+        })
+        .unwrap();
+    calls.set(0);
+
+    assert!(reactor.set_value(input, 2));
+    assert!(reactor.set_value(input, 3));
+    assert_eq!(calls.get(), 0);
+
+    assert_eq!(reactor.value(CellID::Compute(lazy)), Some(30));
+    // the value is kept until the input changes again
+    assert_eq!(reactor.value(CellID::Compute(lazy)), Some(30));
+    assert!(reactor.set_value(input, 3));
+    assert_eq!(reactor.value(CellID::Compute(lazy)), Some(30));
+    assert_eq!(calls.get(), 1);
+
+    assert!(reactor.set_value(input, 4));
+    assert_eq!(reactor.value(CellID::Compute(lazy)), Some(40));
+    assert_eq!(calls.get(), 2);
+}
+
+#[test]
+fn lazy_compute_cells_chain() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let lazy = reactor
+        .create_lazy_compute(&[CellID::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    let lazier = reactor
+        .create_lazy_compute(&[CellID::Compute(lazy), CellID::Input(input)], |v| {
+            v[0] * v[1]
+        })
+        .unwrap();
+
+    assert!(reactor.set_value(input, 2));
+    assert_eq!(reactor.value(CellID::Compute(lazier)), Some(6));
+    assert_eq!(reactor.value(CellID::Compute(lazy)), Some(3));
+    assert_eq!(reactor.recompute_count(lazy), Some(1));
+    assert_eq!(reactor.recompute_count(lazier), Some(1));
+}
+
+#[test]
+fn long_chains_of_lazy_compute_cells() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(0);
+    let mut last = CellID::Input(input);
+    for _ in 0..100_000 {
+        let lazy = reactor.create_lazy_compute(&[last], |v| v[0] + 1).unwrap();
+        last = CellID::Compute(lazy);
+    }
+
+    assert!(reactor.set_value(input, 1));
+    assert_eq!(reactor.value(last), Some(100_001));
+    // and brought up to date for good by a callback
+    assert!(reactor
+        .add_callback(last, |v| cb.callback_called(v))
+        .is_some());
+    assert!(reactor.set_value(input, 2));
+    cb.expect_to_have_been_called_with(100_002);
+}
+
+#[test]
+fn eager_dependents_read_lazy_compute_cells() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let lazy = reactor
+        .create_lazy_compute(&[CellID::Input(input)], |v| v[0] * 2)
+        .unwrap();
+    let eager = reactor
+        .create_compute(&[CellID::Compute(lazy)], |v| v[0] + 1)
+        .unwrap();
+    assert!(reactor
+        .add_callback(eager, |v| cb.callback_called(v))
+        .is_some());
+
+    assert!(reactor.set_value(input, 5));
+    cb.expect_to_have_been_called_with(11);
+    assert_eq!(reactor.recompute_count(lazy), Some(1));
+}
+
+#[test]
+fn lazy_compute_cells_with_callbacks_are_eager() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let lazy = reactor
+        .create_lazy_compute(&[CellID::Input(input)], |v| v[0] * 2)
+        .unwrap();
+    assert!(reactor.set_value(input, 2));
+    assert!(reactor
+        .add_callback(lazy, |v| cb.callback_called(v))
+        .is_some());
+
+    assert!(reactor.set_value(input, 3));
+    cb.expect_to_have_been_called_with(6);
+    assert!(reactor.set_value(input, 3));
+    cb.expect_not_to_have_been_called();
+}
+
+#[test]
+fn set_compute_replaces_the_function_and_dependencies() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let a = reactor.create_input(1);
+    let b = reactor.create_input(10);
+    let output = reactor
+        .create_compute(&[CellID::Input(a)], |v| v[0] + 1)
+        .unwrap();
+    let dependent = reactor
+        .create_compute(&[CellID::Compute(output)], |v| v[0] * 2)
+        .unwrap();
+    assert!(reactor
+        .add_callback(dependent, |v| cb.callback_called(v))
+        .is_some());
+
+    assert_eq!(
+        reactor.set_compute(output, &[CellID::Input(b)], |v| v[0] + 2),
+        Ok(())
+    );
+    assert_eq!(reactor.value(CellID::Compute(output)), Some(12));
+    cb.expect_to_have_been_called_with(24);
+
+    // the old dependency is not one anymore
+    assert!(reactor.set_value(a, 100));
+    cb.expect_not_to_have_been_called();
+    assert!(reactor.set_value(b, 20));
+    cb.expect_to_have_been_called_with(44);
+    assert_eq!(reactor.dependents_of(CellID::Input(a)), Some(vec![]));
+}
+
+#[test]
+fn set_compute_with_the_same_value_calls_no_callbacks() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(2);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] * 2)
+        .unwrap();
+    assert!(reactor
+        .add_callback(output, |v| cb.callback_called(v))
+        .is_some());
+
+    assert!(reactor
+        .set_compute(output, &[CellID::Input(input)], |v| v[0] + v[0])
+        .is_ok());
+    cb.expect_not_to_have_been_called();
+}
+
+#[test]
+fn set_compute_keeps_dependents_in_order() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let first = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0])
+        .unwrap();
+    let deep = reactor
+        .create_compute(&[CellID::Compute(first)], |v| v[0] + 1)
+        .unwrap();
+    let deeper = reactor
+        .create_compute(&[CellID::Compute(deep)], |v| v[0] + 1)
+        .unwrap();
+    let last = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] * 100)
+        .unwrap();
+
+    // `first` now sits below `last`, and everything under it moves down too
+    assert!(reactor
+        .set_compute(first, &[CellID::Compute(last)], |v| v[0])
+        .is_ok());
+    assert_eq!(reactor.depth_of(CellID::Compute(deeper)), Some(4));
+
+    assert!(reactor.set_value(input, 2));
+    assert_eq!(reactor.value(CellID::Compute(deeper)), Some(202));
+    // and each of them got recomputed once, after `last`
+    assert_eq!(reactor.recompute_count(deeper), Some(2));
+}
+
+#[test]
+fn error_setting_compute_with_a_cycle() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    let dependent = reactor
+        .create_compute(&[CellID::Compute(output)], |v| v[0] + 1)
+        .unwrap();
+
+    assert_eq!(
+        reactor.set_compute(output, &[CellID::Compute(output)], |v| v[0]),
+        Err(SetComputeError::Cycle(CellID::Compute(output)))
+    );
+    assert_eq!(
+        reactor.set_compute(
+            output,
+            &[CellID::Input(input), CellID::Compute(dependent)],
+            |v| v[0] + v[1]
+        ),
+        Err(SetComputeError::Cycle(CellID::Compute(dependent)))
+    );
+
+    // nothing has changed
+    assert!(reactor.set_value(input, 2));
+    assert_eq!(reactor.value(CellID::Compute(dependent)), Some(4));
+}
+
+#[test]
+fn error_setting_compute_of_a_nonexistent_cell() {
+    let mut dummy_reactor = Reactor::new();
+    let input = dummy_reactor.create_input(1);
+    let output = dummy_reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0])
+        .unwrap();
+
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    assert_eq!(
+        reactor.set_compute(output, &[CellID::Input(input)], |v| v[0]),
+        Err(SetComputeError::NonexistentCell)
+    );
+
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0])
+        .unwrap();
+    reactor.remove_cell(CellID::Compute(output)).unwrap();
+    let other = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0])
+        .unwrap();
+    assert_eq!(
+        reactor.set_compute(other, &[CellID::Compute(output)], |v| v[0]),
+        Err(SetComputeError::NonexistentDependency(CellID::Compute(
+            output
+        )))
+    );
+}
+
+#[test]
+fn error_removing_a_callback_of_another_cell() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    let callback = reactor
+        .add_callback(output, |v| cb.callback_called(v))
+        .unwrap();
+
+    assert_eq!(
+        reactor.remove_callback(input, callback),
+        Err(RemoveCallbackError::NonexistentCallback)
+    );
+    assert!(reactor.set_value(input, 2));
+    cb.expect_to_have_been_called_with(3);
+}
+
+#[test]
+fn error_removing_a_callback_of_a_removed_cell() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let removed = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0])
+        .unwrap();
+    let callback = reactor.add_callback(removed, |_| {}).unwrap();
+    reactor.remove_cell(CellID::Compute(removed)).unwrap();
+
+    // most likely in the slot of the removed one
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0])
+        .unwrap();
+    assert!(reactor.add_callback(output, |_| {}).is_some());
+    assert_eq!(
+        reactor.remove_callback(output, callback),
+        Err(RemoveCallbackError::NonexistentCallback)
+    );
+}
+
+#[test]
+fn callback_ids_are_not_reused() {
+    let cb1 = CallbackRecorder::new();
+    let cb2 = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let first = reactor
+        .add_callback(input, |v| cb1.callback_called(v))
+        .unwrap();
+    assert_eq!(reactor.remove_callback(input, first), Ok(()));
+    let second = reactor
+        .add_callback(input, |v| cb2.callback_called(v))
+        .unwrap();
+    assert_ne!(first, second);
+
+    assert_eq!(
+        reactor.remove_callback(input, first),
+        Err(RemoveCallbackError::NonexistentCallback)
+    );
+    assert!(reactor.set_value(input, 2));
+    cb2.expect_to_have_been_called_with(2);
+}
+
+#[test]
+fn scoped_callbacks_stop_with_their_subscription() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] * 2)
+        .unwrap();
+    let subscription = reactor
+        .add_scoped_callback(output, |v| cb.callback_called(v))
+        .unwrap();
+
+    assert!(reactor.set_value(input, 2));
+    cb.expect_to_have_been_called_with(4);
+    drop(subscription);
+    assert!(reactor.set_value(input, 3));
+    cb.expect_not_to_have_been_called();
+}
+
+#[test]
+fn dropped_subscriptions_do_not_pile_up() {
+    let held = std::rc::Rc::new(());
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    for _ in 0..10 {
+        let held = std::rc::Rc::clone(&held);
+        let subscription = reactor
+            .add_scoped_callback(input, move |_| {
+                let _ = &held;
+            })
+            .unwrap();
+        drop(subscription);
+    }
+    // the value never changed, each callback added drops the ones before
+    assert_eq!(std::rc::Rc::strong_count(&held), 2);
+    assert!(reactor.add_callback(input, |_| {}).is_some());
+    assert_eq!(std::rc::Rc::strong_count(&held), 1);
+}
+
+#[test]
+fn error_adding_a_scoped_callback_to_a_nonexistent_cell() {
+    let mut dummy_reactor = Reactor::new();
+    let input = dummy_reactor.create_input(1);
+
+    let mut reactor: Reactor<i32> = Reactor::new();
+    assert!(reactor.add_scoped_callback(input, |_| {}).is_none());
+}
+
+#[test]
+fn undo_and_redo_input_changes() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    reactor.record_history(10);
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] + 1)
+        .unwrap();
+    assert!(reactor
+        .add_callback(output, |v| cb.callback_called(v))
+        .is_some());
+
+    assert!(reactor.set_value(input, 2));
+    cb.expect_to_have_been_called_with(3);
+    assert!(reactor.set_value(input, 3));
+    cb.expect_to_have_been_called_with(4);
+
+    assert!(reactor.undo());
+    cb.expect_to_have_been_called_with(3);
+    assert!(reactor.undo());
+    cb.expect_to_have_been_called_with(2);
+    assert_eq!(reactor.value(CellID::Input(input)), Some(1));
+    assert!(!reactor.undo());
+
+    assert!(reactor.redo());
+    cb.expect_to_have_been_called_with(3);
+    assert_eq!(reactor.value(CellID::Compute(output)), Some(3));
+}
+
+#[test]
+fn undo_a_batch_as_a_single_change() {
+    let cb = CallbackRecorder::new();
+    let mut reactor = Reactor::new();
+    reactor.record_history(10);
+    let a = reactor.create_input(1);
+    let b = reactor.create_input(2);
+    let sum = reactor
+        .create_compute(&[CellID::Input(a), CellID::Input(b)], |v| v[0] + v[1])
+        .unwrap();
+    assert!(reactor
+        .add_callback(sum, |v| cb.callback_called(v))
+        .is_some());
+
+    reactor.batch(|tx| {
+        tx.set(a, 10);
+        tx.set(b, 20);
+    });
+    cb.expect_to_have_been_called_with(30);
+
+    assert!(reactor.undo());
+    cb.expect_to_have_been_called_with(3);
+    assert_eq!(reactor.value(CellID::Input(a)), Some(1));
+    assert_eq!(reactor.value(CellID::Input(b)), Some(2));
+}
+
+#[test]
+fn a_new_change_drops_the_undone_ones() {
+    let mut reactor = Reactor::new();
+    reactor.record_history(10);
+    let input = reactor.create_input(1);
+    assert!(reactor.set_value(input, 2));
+    assert!(reactor.undo());
+    assert!(reactor.set_value(input, 3));
+    assert!(!reactor.redo());
+    assert!(reactor.undo());
+    assert_eq!(reactor.value(CellID::Input(input)), Some(1));
+}
+
+#[test]
+fn history_keeps_only_the_latest_changes() {
+    let mut reactor = Reactor::new();
+    reactor.record_history(2);
+    let input = reactor.create_input(0);
+    for i in 1..=4 {
+        assert!(reactor.set_value(input, i));
+    }
+    assert!(reactor.undo());
+    assert!(reactor.undo());
+    assert!(!reactor.undo());
+    assert_eq!(reactor.value(CellID::Input(input)), Some(2));
+
+    reactor.record_history(0);
+    assert!(!reactor.redo());
+    assert!(!reactor.undo());
+}
+
+#[test]
+fn nothing_to_undo_without_history() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    assert!(reactor.set_value(input, 2));
+    assert!(!reactor.undo());
+    assert!(!reactor.redo());
+}
@@ -0,0 +1,260 @@
+use react::*;
+
+#[test]
+fn formulas_follow_their_inputs() {
+    let mut sheet = Sheet::new();
+    sheet.define("A1", "2").unwrap();
+    sheet.define("A2", "3").unwrap();
+    sheet.define("B1", "=A1 * A2 + 1").unwrap();
+    sheet.define("b2", "=-(b1 - 10) / 2").unwrap();
+    assert_eq!(sheet.value("B1"), Some(7.0));
+    assert_eq!(sheet.value("B2"), Some(1.5));
+
+    sheet.define("A2", "4").unwrap();
+    assert_eq!(sheet.value("B1"), Some(9.0));
+    assert_eq!(sheet.value("B2"), Some(0.5));
+}
+
+#[test]
+fn operators_have_the_usual_precedence() {
+    let mut sheet = Sheet::new();
+    for (formula, expected) in [
+        ("=1 + 2 * 3", 7.0),
+        ("=(1 + 2) * 3", 9.0),
+        ("=8 / 4 / 2", 1.0),
+        ("=2 - -3", 5.0),
+        ("=1 + 2 > 2", 1.0),
+        ("=1 + 2 <> 3", 0.0),
+        ("=2 <= 1.5", 0.0),
+    ] {
+        sheet
+            .define("A1", formula)
+            .unwrap_or_else(|e| panic!("{}: {:?}", formula, e));
+        assert_eq!(sheet.value("A1"), Some(expected), "{}", formula);
+    }
+}
+
+#[test]
+fn functions_and_ranges() {
+    let mut sheet = Sheet::new();
+    sheet.import("1\t2\t3\n4\t5\t6").unwrap();
+    sheet.define("D1", "=SUM(A1:C2)").unwrap();
+    sheet.define("D2", "=MIN(B2:A1, 10) + MAX(C1, C2)").unwrap();
+    sheet.define("D3", "=IF(D1 > 20, 1, 0)").unwrap();
+    assert_eq!(sheet.value("D1"), Some(21.0));
+    assert_eq!(sheet.value("D2"), Some(7.0));
+    assert_eq!(sheet.value("D3"), Some(1.0));
+
+    sheet.define("A1", "0").unwrap();
+    assert_eq!(sheet.value("D3"), Some(0.0));
+}
+
+#[test]
+fn import_orders_formulas_by_their_references() {
+    let mut sheet = Sheet::new();
+    sheet
+        .import("=B1 + C1\t=C1 * 2\t10\n\t\n=A1 + A4\t\t\n5")
+        .unwrap();
+    assert_eq!(sheet.value("A1"), Some(30.0));
+    assert_eq!(sheet.value("A3"), Some(35.0));
+    assert_eq!(sheet.value("B2"), None);
+}
+
+#[test]
+fn redefined_formulas_keep_their_callbacks() {
+    let mut sheet = Sheet::new();
+    sheet.import("1\t2\n=A1").unwrap();
+    let id = sheet.id("A2").unwrap();
+    let values = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let recorded = values.clone();
+    sheet
+        .reactor()
+        .add_callback(id, move |v| recorded.borrow_mut().push(v))
+        .unwrap();
+
+    assert_eq!(sheet.define("A2", "=B1"), Ok(id));
+    sheet.define("B1", "3").unwrap();
+    assert_eq!(*values.borrow(), [2.0, 3.0]);
+}
+
+#[test]
+fn error_parsing_a_formula() {
+    let mut sheet = Sheet::new();
+    sheet.define("A1", "1").unwrap();
+    let parse_error = |sheet: &mut Sheet, source| match sheet.define("B1", source) {
+        Err(FormulaError::Parse { position, .. }) => position,
+        other => panic!("{}: {:?}", source, other),
+    };
+    assert_eq!(parse_error(&mut sheet, "one"), 0);
+    assert_eq!(parse_error(&mut sheet, "=1 +"), 4);
+    assert_eq!(parse_error(&mut sheet, " =(A1 * 2"), 9);
+    assert_eq!(parse_error(&mut sheet, "=A1 A1"), 4);
+    assert_eq!(parse_error(&mut sheet, "=1 $ 2"), 3);
+    assert_eq!(parse_error(&mut sheet, "=SUM(A1:2)"), 8);
+    assert_eq!(sheet.value("B1"), None);
+}
+
+#[test]
+fn error_referring_to_unknown_things() {
+    let mut sheet = Sheet::new();
+    assert_eq!(
+        sheet.define("A1", "=B1 + 1"),
+        Err(FormulaError::UnknownReference("B1".into()))
+    );
+    assert_eq!(
+        sheet.define("A1", "=AVG(1)"),
+        Err(FormulaError::UnknownFunction("AVG".into()))
+    );
+    assert_eq!(
+        sheet.define("A1", "=IF(1, 2)"),
+        Err(FormulaError::WrongArgumentCount("IF".into()))
+    );
+    assert_eq!(
+        sheet.define("A1", "=SUM()"),
+        Err(FormulaError::WrongArgumentCount("SUM".into()))
+    );
+    assert_eq!(
+        sheet.define("1A", "1"),
+        Err(FormulaError::InvalidName("1A".into()))
+    );
+}
+
+#[test]
+fn error_making_a_cycle() {
+    let mut sheet = Sheet::new();
+    sheet.import("1\t=A1\t=B1").unwrap();
+    assert_eq!(
+        sheet.define("B1", "=C1"),
+        Err(FormulaError::Cycle("B1".into()))
+    );
+    assert_eq!(sheet.value("C1"), Some(1.0));
+
+    let error = Sheet::new().import("=B1\t=A1").unwrap_err();
+    assert_eq!(error.error, FormulaError::Cycle(error.cell.clone()));
+}
+
+#[test]
+fn error_changing_the_kind_of_a_cell() {
+    let mut sheet = Sheet::new();
+    sheet.import("1\t=A1").unwrap();
+    assert_eq!(
+        sheet.define("A1", "=2"),
+        Err(FormulaError::KindChange("A1".into()))
+    );
+    assert_eq!(
+        sheet.define("B1", "2"),
+        Err(FormulaError::KindChange("B1".into()))
+    );
+}
+
+#[test]
+fn error_importing_tells_the_cell() {
+    let mut sheet = Sheet::new();
+    assert_eq!(
+        sheet.import("1\t2\n3\t=A1 + Z9"),
+        Err(ImportError {
+            cell: "B2".into(),
+            error: FormulaError::UnknownReference("Z9".into()),
+        })
+    );
+    // the cells before the error are there
+    assert_eq!(sheet.value("A2"), Some(3.0));
+}
+
+#[test]
+fn ranges_leave_out_the_cells_not_defined() {
+    let mut sheet = Sheet::new();
+    sheet
+        .import("1\n\n3\n=SUM(A1:A3)\t=MIN(A2:A3)\t=MAX(C9:D9)")
+        .unwrap();
+    assert_eq!(sheet.value("A4"), Some(4.0));
+    assert_eq!(sheet.value("B4"), Some(3.0));
+    assert_eq!(sheet.value("C4"), Some(0.0));
+
+    // but not the cells referred to on their own
+    assert_eq!(
+        sheet.define("D1", "=SUM(A1:A3) + A2"),
+        Err(FormulaError::UnknownReference("A2".into()))
+    );
+}
+
+#[test]
+fn large_ranges() {
+    let mut sheet = Sheet::new();
+    sheet.define("A1", "1").unwrap();
+    sheet.define("B1", "=SUM(A1:A10000)").unwrap();
+    assert_eq!(sheet.value("B1"), Some(1.0));
+    assert!(matches!(
+        sheet.define("B1", "=SUM(A1:A100000)"),
+        Err(FormulaError::Parse { position: 5, .. })
+    ));
+    assert!(matches!(
+        sheet.define("B1", "=SUM(A1:ZZZZZZZZZZZZZ1)"),
+        Err(FormulaError::Parse { position: 5, .. })
+    ));
+}
+
+#[test]
+fn error_with_names_too_long() {
+    let mut sheet = Sheet::new();
+    assert_eq!(
+        sheet.define("AAAAAAAAAAAAAAAA1", "1"),
+        Err(FormulaError::InvalidName("AAAAAAAAAAAAAAAA1".into()))
+    );
+    assert!(matches!(
+        sheet.define("A1", "=AAAAAAAAAAAAAAAA1"),
+        Err(FormulaError::Parse { position: 1, .. })
+    ));
+    assert!(matches!(
+        sheet.define("A1", "=A99999999999999999999999"),
+        Err(FormulaError::Parse { position: 1, .. })
+    ));
+}
+
+#[test]
+fn deep_formulas() {
+    let mut sheet = Sheet::new();
+    let nested = format!("={}1{}", "(".repeat(100), ")".repeat(100));
+    sheet.define("A1", &nested).unwrap();
+    assert_eq!(sheet.value("A1"), Some(1.0));
+    sheet
+        .define("A2", &format!("={}1", "-".repeat(100)))
+        .unwrap();
+    assert_eq!(sheet.value("A2"), Some(1.0));
+    sheet
+        .define("A3", &format!("=1{}", "+1".repeat(200_000)))
+        .unwrap();
+    assert_eq!(sheet.value("A3"), Some(200_001.0));
+
+    for source in [
+        format!("={}1", "-".repeat(200_000)),
+        format!("={}1{}", "(".repeat(200_000), ")".repeat(200_000)),
+        format!("={}1{}", "SUM(".repeat(101), ")".repeat(101)),
+    ] {
+        assert!(matches!(
+            sheet.define("B1", &source),
+            Err(FormulaError::Parse { .. })
+        ));
+    }
+}
+
+#[test]
+fn import_long_chains_of_formulas() {
+    let rows = 30_000;
+    let mut grid = (1..rows)
+        .map(|row| format!("=A{} + 1\n", row + 1))
+        .collect::<String>();
+    grid.push('0');
+    let mut sheet = Sheet::new();
+    sheet.import(&grid).unwrap();
+    assert_eq!(sheet.value("A1"), Some((rows - 1) as f64));
+
+    // A1 only refers to the cycle
+    assert_eq!(
+        Sheet::new().import("=B1\t=C1\t=B1"),
+        Err(ImportError {
+            cell: "B1".into(),
+            error: FormulaError::Cycle("B1".into()),
+        })
+    );
+}
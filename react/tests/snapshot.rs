@@ -0,0 +1,188 @@
+use react::*;
+
+fn registry<'a>() -> Registry<'a, i32> {
+    let mut registry = Registry::new();
+    registry.register("plus_one", |v: &[i32]| v[0] + 1);
+    registry.register("sum", |v: &[i32]| v.iter().sum());
+    registry
+}
+
+#[test]
+fn restored_reactor_has_the_same_values_and_ids() {
+    let registry = registry();
+    let mut reactor = Reactor::new();
+    let a = reactor.create_input(1);
+    let b = reactor.create_input(10);
+    let plus_one = reactor
+        .create_registered(&registry, "plus_one", &[CellID::Input(a)])
+        .unwrap();
+    let sum = reactor
+        .create_registered(
+            &registry,
+            "sum",
+            &[CellID::Compute(plus_one), CellID::Input(b)],
+        )
+        .unwrap();
+    reactor.set_value(a, 2);
+
+    let snapshot = reactor.snapshot().unwrap();
+    let mut restored = Reactor::restore(&snapshot, &registry).unwrap();
+    assert_eq!(restored.value(CellID::Input(a)), Some(2));
+    assert_eq!(restored.value(CellID::Compute(plus_one)), Some(3));
+    assert_eq!(restored.value(CellID::Compute(sum)), Some(13));
+
+    // and it keeps on reacting
+    assert!(restored.set_value(b, 20));
+    assert_eq!(restored.value(CellID::Compute(sum)), Some(23));
+    assert_eq!(restored.snapshot(), restored.snapshot());
+}
+
+#[test]
+fn removed_cells_stay_removed_after_restore() {
+    let registry = registry();
+    let mut reactor = Reactor::new();
+    let removed = reactor.create_input(1);
+    let input = reactor.create_input(2);
+    reactor.remove_cell(CellID::Input(removed)).unwrap();
+
+    let mut restored = Reactor::restore(&reactor.snapshot().unwrap(), &registry).unwrap();
+    assert_eq!(restored.value(CellID::Input(removed)), None);
+    assert_eq!(restored.value(CellID::Input(input)), Some(2));
+
+    // the freed slot gets reused without reviving the old id
+    let new = restored.create_input(3);
+    assert_eq!(restored.value(CellID::Input(new)), Some(3));
+    assert_eq!(restored.value(CellID::Input(removed)), None);
+}
+
+#[test]
+fn error_snapshotting_an_unregistered_compute_cell() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0])
+        .unwrap();
+    assert_eq!(
+        reactor.snapshot(),
+        Err(SnapshotError::UnnamedCompute(output))
+    );
+}
+
+#[test]
+fn error_creating_a_cell_with_an_unknown_function() {
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    assert_eq!(
+        reactor.create_registered(&registry(), "minus_one", &[CellID::Input(input)]),
+        Err(SnapshotError::UnknownFunction("minus_one".into()))
+    );
+}
+
+#[test]
+fn error_restoring_with_an_unknown_function() {
+    let registry = registry();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    reactor
+        .create_registered(&registry, "plus_one", &[CellID::Input(input)])
+        .unwrap();
+    let snapshot = reactor.snapshot().unwrap();
+
+    let mut other = Registry::new();
+    other.register("sum", |v: &[i32]| v.iter().sum());
+    assert!(matches!(
+        Reactor::restore(&snapshot, &other),
+        Err(SnapshotError::UnknownFunction(name)) if name == "plus_one"
+    ));
+}
+
+#[test]
+fn error_restoring_a_compute_cell_before_its_dependency() {
+    let registry = registry();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    reactor
+        .create_registered(&registry, "plus_one", &[CellID::Input(input)])
+        .unwrap();
+    let mut snapshot = reactor.snapshot().unwrap();
+
+    snapshot.cells.reverse();
+    assert!(matches!(
+        Reactor::restore(&snapshot, &registry),
+        Err(SnapshotError::MissingDependency(CellID::Input(id))) if id == input
+    ));
+}
+
+#[test]
+fn restored_fallible_cells_fail_again() {
+    let mut registry = Registry::new_fallible();
+    registry.register_try("checked_div", |v: &[i32]| {
+        v[0].checked_div(v[1]).ok_or("division by zero")
+    });
+    let mut reactor = Reactor::new_fallible();
+    let a = reactor.create_input(10);
+    let b = reactor.create_input(0);
+    let div = reactor
+        .create_registered(
+            &registry,
+            "checked_div",
+            &[CellID::Input(a), CellID::Input(b)],
+        )
+        .unwrap();
+
+    let mut restored = Reactor::restore(&reactor.snapshot().unwrap(), &registry).unwrap();
+    assert_eq!(
+        restored.try_value(CellID::Compute(div)),
+        Some(Err("division by zero"))
+    );
+    restored.set_value(b, 5);
+    assert_eq!(restored.value(CellID::Compute(div)), Some(2));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn snapshot_survives_a_json_roundtrip() {
+    let registry = registry();
+    let mut reactor = Reactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_registered(&registry, "plus_one", &[CellID::Input(input)])
+        .unwrap();
+    reactor.set_value(input, 41);
+
+    let json = serde_json::to_string(&reactor.snapshot().unwrap()).unwrap();
+    let snapshot: Snapshot<i32> = serde_json::from_str(&json).unwrap();
+    let restored = Reactor::restore(&snapshot, &registry).unwrap();
+    assert_eq!(restored.value(CellID::Compute(output)), Some(42));
+}
+
+#[test]
+fn registered_functions_can_borrow_the_values() {
+    let mut registry = Registry::new_fallible();
+    registry.register_try_ref("first_word", |v: &[&String]| {
+        v[0].split_whitespace()
+            .next()
+            .map(String::from)
+            .ok_or("no words")
+    });
+    let mut reactor = Reactor::new_fallible();
+    let text = reactor.create_input(String::from("hello world"));
+    let word = reactor
+        .create_registered(&registry, "first_word", &[CellID::Input(text)])
+        .unwrap();
+    assert_eq!(
+        reactor.value(CellID::Compute(word)),
+        Some(String::from("hello"))
+    );
+
+    let restored = Reactor::restore(&reactor.snapshot().unwrap(), &registry).unwrap();
+    assert_eq!(
+        restored.value(CellID::Compute(word)),
+        Some(String::from("hello"))
+    );
+    reactor.set_value(text, String::from(" "));
+    assert_eq!(
+        reactor.try_value(CellID::Compute(word)),
+        Some(Err("no words"))
+    );
+}
@@ -0,0 +1,176 @@
+use react::*;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[test]
+fn sync_reactor_works_like_a_reactor() {
+    let reactor = SyncReactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] * 10)
+        .unwrap();
+    assert!(reactor.set_value(input, 2));
+    assert_eq!(reactor.value(CellID::Compute(output)), Some(20));
+    assert_eq!(
+        reactor.with(move |r| r.remove_cell(CellID::Compute(output))),
+        Ok(())
+    );
+    assert_eq!(reactor.value(CellID::Compute(output)), None);
+}
+
+#[test]
+fn sync_reactor_can_be_used_from_many_threads() {
+    let reactor = SyncReactor::new();
+    let inputs: Vec<_> = (0..4).map(|_| reactor.create_input(0)).collect();
+    let deps: Vec<_> = inputs.iter().map(|&i| CellID::Input(i)).collect();
+    let sum = reactor
+        .create_compute(&deps, |v| v.iter().sum::<i32>())
+        .unwrap();
+
+    let handles: Vec<_> = inputs
+        .into_iter()
+        .map(|input| {
+            let reactor = reactor.clone();
+            thread::spawn(move || {
+                for i in 1..=100 {
+                    assert!(reactor.set_value(input, i));
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(reactor.value(CellID::Compute(sum)), Some(400));
+}
+
+#[test]
+fn observers_see_consistent_snapshots() {
+    let reactor = SyncReactor::new();
+    let a = reactor.create_input(0);
+    let b = reactor.create_input(0);
+    let diff = reactor
+        .create_compute(&[CellID::Input(a), CellID::Input(b)], |v| v[0] - v[1])
+        .unwrap();
+
+    let writer = {
+        let reactor = reactor.clone();
+        thread::spawn(move || {
+            for i in 1..=200 {
+                reactor.batch(move |tx| {
+                    tx.set(a, i);
+                    tx.set(b, i);
+                });
+            }
+        })
+    };
+    for _ in 0..200 {
+        let values = reactor.values(&[CellID::Input(a), CellID::Input(b), CellID::Compute(diff)]);
+        assert_eq!(values[0], values[1]);
+        assert_eq!(values[2], Some(0));
+    }
+    writer.join().unwrap();
+}
+
+#[test]
+fn sync_reactor_callbacks_run_on_change() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let reactor = SyncReactor::new();
+    let input = reactor.create_input(1);
+    let output = reactor
+        .create_compute(&[CellID::Input(input)], |v| v[0] / 2)
+        .unwrap();
+    let callback = {
+        let seen = Arc::clone(&seen);
+        reactor
+            .add_callback(output, move |v| seen.lock().unwrap().push(v))
+            .unwrap()
+    };
+
+    assert!(reactor.set_value(input, 2));
+    assert!(reactor.set_value(input, 3));
+    assert!(reactor.set_value(input, 4));
+    assert_eq!(reactor.remove_callback(output, callback), Ok(()));
+    assert!(reactor.set_value(input, 6));
+    assert_eq!(*seen.lock().unwrap(), vec![1, 2]);
+}
+
+#[test]
+fn sync_reactor_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SyncReactor<String>>();
+}
+
+#[test]
+fn sync_reactor_scoped_callbacks_stop_once_dropped() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let reactor = SyncReactor::new();
+    let input = reactor.create_input(1);
+    let subscription = {
+        let seen = Arc::clone(&seen);
+        reactor
+            .add_scoped_callback(input, move |v| seen.lock().unwrap().push(v))
+            .unwrap()
+    };
+
+    assert!(reactor.set_value(input, 2));
+    // dropped on another thread than the reactor's
+    thread::spawn(move || drop(subscription)).join().unwrap();
+    assert!(reactor.set_value(input, 3));
+    assert_eq!(*seen.lock().unwrap(), vec![2]);
+}
+
+#[test]
+fn sync_reactor_called_from_a_callback_panics_instead_of_blocking() {
+    let reactor = SyncReactor::new();
+    let input = reactor.create_input(1);
+    let other = reactor.create_input(1);
+    let outcome = Arc::new(Mutex::new(None));
+    let id = {
+        let (handle, outcome) = (reactor.clone(), Arc::clone(&outcome));
+        reactor
+            .add_callback(input, move |v| {
+                let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    handle.set_value(other, v)
+                }));
+                *outcome.lock().unwrap() = Some(result.is_err());
+            })
+            .unwrap()
+    };
+
+    assert!(reactor.set_value(input, 2));
+    assert_eq!(*outcome.lock().unwrap(), Some(true));
+    assert_eq!(reactor.value(CellID::Input(other)), Some(1));
+    // dropping the handle it holds, so that the thread can stop
+    assert_eq!(reactor.remove_callback(input, id), Ok(()));
+}
+
+#[test]
+fn sync_reactor_shutdown_stops_the_thread_despite_the_handles_it_holds() {
+    struct DropFlag(Arc<Mutex<bool>>);
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            *self.0.lock().unwrap() = true;
+        }
+    }
+
+    let dropped = Arc::new(Mutex::new(false));
+    let reactor = SyncReactor::new();
+    let input = reactor.create_input(1);
+    {
+        let (handle, flag) = (reactor.clone(), DropFlag(Arc::clone(&dropped)));
+        reactor
+            .add_callback(input, move |_| {
+                let _ = (&handle, &flag);
+            })
+            .unwrap();
+    }
+
+    let other = reactor.clone();
+    thread::spawn(move || other.shutdown()).join().unwrap();
+    assert!(*dropped.lock().unwrap());
+    reactor.shutdown();
+    let result = std::panic::catch_unwind(|| reactor.value(CellID::Input(input)));
+    assert!(result.is_err());
+}
@@ -0,0 +1,188 @@
+use react::*;
+
+#[test]
+fn compute_cells_can_change_the_value_type() {
+    let mut reactor = TypedReactor::new();
+    let count = reactor.create_input(3usize);
+    let stars = reactor
+        .create_compute(count, |n: &usize| "*".repeat(*n))
+        .unwrap();
+    let width = reactor
+        .create_compute(stars, |s: &String| s.len() as f64)
+        .unwrap();
+    assert_eq!(reactor.value(stars), Some(String::from("***")));
+
+    assert!(reactor.set_value(count, 5));
+    assert_eq!(reactor.value(stars), Some(String::from("*****")));
+    assert_eq!(reactor.value(width), Some(5.0));
+}
+
+#[test]
+fn compute_cells_can_mix_dependency_types() {
+    let mut reactor = TypedReactor::new();
+    let name = reactor.create_input(String::from("apple"));
+    let count = reactor.create_input(1u32);
+    let enabled = reactor.create_input(true);
+    let label = reactor
+        .create_compute3(
+            name,
+            count,
+            enabled,
+            |name: &String, count: &u32, enabled: &bool| {
+                if *enabled {
+                    format!("{} x{}", name, count)
+                } else {
+                    String::new()
+                }
+            },
+        )
+        .unwrap();
+    let sum = reactor
+        .create_compute2(count, label, |count: &u32, label: &String| {
+            *count as usize + label.len()
+        })
+        .unwrap();
+
+    assert!(reactor.set_value(count, 12));
+    assert_eq!(reactor.value(label), Some(String::from("apple x12")));
+    assert_eq!(reactor.value(sum), Some(21));
+    assert!(reactor.set_value(enabled, false));
+    assert_eq!(reactor.value(label), Some(String::new()));
+    assert_eq!(reactor.value(sum), Some(12));
+}
+
+#[test]
+fn compute_cells_can_take_many_dependencies_of_one_type() {
+    let mut reactor = TypedReactor::new();
+    let a = reactor.create_input(1i64);
+    let b = reactor.create_input(2i64);
+    let doubled = reactor.create_compute(b, |b: &i64| b * 2).unwrap();
+    let total = reactor
+        .create_compute_all(&[a.into(), b.into(), doubled.into()], |v: &[&i64]| {
+            v.iter().copied().sum::<i64>().to_string()
+        })
+        .unwrap();
+    assert_eq!(reactor.value(total), Some(String::from("7")));
+    assert!(reactor.set_value(b, 10));
+    assert_eq!(reactor.value(total), Some(String::from("31")));
+}
+
+#[test]
+fn callbacks_fire_only_when_the_typed_value_changes() {
+    let seen = std::cell::RefCell::new(Vec::new());
+    let mut reactor = TypedReactor::new();
+    let input = reactor.create_input(1i32);
+    let parity = reactor
+        .create_compute(input, |v: &i32| if v % 2 == 0 { "even" } else { "odd" })
+        .unwrap();
+    let callback = reactor
+        .add_callback(parity, |v: &&str| seen.borrow_mut().push(*v))
+        .unwrap();
+
+    assert!(reactor.set_value(input, 3));
+    assert!(reactor.set_value(input, 4));
+    assert!(reactor.set_value(input, 6));
+    assert_eq!(reactor.remove_callback(parity, callback), Ok(()));
+    assert!(reactor.set_value(input, 7));
+    assert_eq!(*seen.borrow(), vec!["even"]);
+    assert_eq!(
+        reactor.remove_callback(parity, callback),
+        Err(RemoveCallbackError::NonexistentCallback)
+    );
+}
+
+#[test]
+fn handles_from_another_reactor_are_rejected() {
+    let mut dummy_reactor = TypedReactor::new();
+    let dummy_text = dummy_reactor.create_input(String::from("text"));
+    let dummy_output = dummy_reactor
+        .create_compute(dummy_text, |s: &String| s.len())
+        .unwrap();
+    let dummy_callback = dummy_reactor
+        .add_callback(dummy_output, |_: &usize| ())
+        .unwrap();
+
+    // same slot, different type
+    let mut reactor = TypedReactor::new();
+    let input = reactor.create_input(1u8);
+    assert_eq!(reactor.value(dummy_text), None);
+    assert!(!reactor.set_value(dummy_text, String::from("other")));
+    assert_eq!(
+        reactor.create_compute(dummy_text, |s: &String| s.len()),
+        None
+    );
+    assert_eq!(reactor.add_callback(dummy_output, |_: &usize| ()), None);
+    assert_eq!(
+        reactor.remove_callback(dummy_output, dummy_callback),
+        Err(RemoveCallbackError::NonexistentCell)
+    );
+    assert_eq!(reactor.value(input), Some(1));
+}
+
+#[test]
+fn handles_of_the_same_type_from_another_reactor_are_rejected() {
+    let mut dummy_reactor = TypedReactor::new();
+    let dummy_input = dummy_reactor.create_input(1);
+    let dummy_output = dummy_reactor
+        .create_compute(dummy_input, |v: &i32| v + 1)
+        .unwrap();
+
+    // same slots, same types
+    let mut reactor = TypedReactor::new();
+    let input = reactor.create_input(10);
+    let output = reactor.create_compute(input, |v: &i32| v * 2).unwrap();
+    assert_ne!(input, dummy_input);
+    assert_eq!(reactor.value(dummy_input), None);
+    assert_eq!(reactor.value(dummy_output), None);
+    assert!(!reactor.set_value(dummy_input, 2));
+    assert_eq!(reactor.create_compute(dummy_output, |v: &i32| *v), None);
+    assert_eq!(reactor.add_callback(dummy_output, |_: &i32| ()), None);
+    assert_eq!(reactor.value(output), Some(20));
+}
+
+#[test]
+fn error_removing_a_callback_of_another_cell() {
+    let mut reactor = TypedReactor::new();
+    let input = reactor.create_input(1);
+    let double = reactor.create_compute(input, |v: &i32| v * 2).unwrap();
+    let triple = reactor.create_compute(input, |v: &i32| v * 3).unwrap();
+    let callback = reactor.add_callback(double, |_: &i32| {}).unwrap();
+    assert_eq!(
+        reactor.remove_callback(triple, callback),
+        Err(RemoveCallbackError::NonexistentCallback)
+    );
+    assert_eq!(reactor.remove_callback(double, callback), Ok(()));
+}
+
+#[test]
+fn error_removing_a_callback_of_another_reactor() {
+    let mut dummy_reactor = TypedReactor::new();
+    let dummy_input = dummy_reactor.create_input(1);
+    let dummy_output = dummy_reactor
+        .create_compute(dummy_input, |v: &i32| v + 1)
+        .unwrap();
+    let dummy_callback = dummy_reactor
+        .add_callback(dummy_output, |_: &i32| ())
+        .unwrap();
+
+    // same slots, same key
+    let fired = std::cell::Cell::new(0);
+    let mut reactor = TypedReactor::new();
+    let input = reactor.create_input(10);
+    let output = reactor.create_compute(input, |v: &i32| v * 2).unwrap();
+    let callback = reactor
+        .add_callback(output, |_: &i32| fired.set(fired.get() + 1))
+        .unwrap();
+    assert_ne!(callback, dummy_callback);
+    assert_eq!(
+        reactor.remove_callback(output, dummy_callback),
+        Err(RemoveCallbackError::NonexistentCallback)
+    );
+    reactor.set_value(input, 11);
+    assert_eq!(fired.get(), 1);
+    assert_eq!(
+        dummy_reactor.remove_callback(dummy_output, callback),
+        Err(RemoveCallbackError::NonexistentCallback)
+    );
+    assert_eq!(reactor.remove_callback(output, callback), Ok(()));
+}